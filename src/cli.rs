@@ -0,0 +1,152 @@
+use std::{env, process::exit};
+
+use crate::{
+    codemod_models::ResourceRouteForm, data_hooks::DataHooks,
+    definition_helpers::DefinitionHelpers, route_imports::RouteImportMode,
+};
+
+pub struct Args {
+    pub command: Command,
+    /// Path to the Remix app, defaults to the current directory
+    pub dir: Option<String>,
+    pub source_map: Option<SourceMapOutput>,
+    /// Import from react-router instead of the `@remix-run/*` packages
+    pub rewrite_imports: bool,
+    /// Built-in hooks plus `--preset` and `--hook` mappings
+    pub data_hooks: DataHooks,
+    /// What to do with imports of moved route exports in other modules
+    pub route_imports: RouteImportMode,
+    /// Default export of routes without a component
+    pub resource_route: Option<ResourceRouteForm>,
+    /// Built-in single fetch helpers plus `--definition-helper` names
+    pub definition_helpers: DefinitionHelpers,
+    /// Rewrite URLs of links, forms, navigations and redirects to `href()`
+    pub typed_links: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Convert classic route exports to defineRoute
+    Migrate,
+    /// Convert defineRoute back to classic route exports
+    Revert,
+    /// Keep classic route exports, typed with React Router v7 generated types
+    Typegen,
+    /// Generate a React Router `app/routes.ts` from the route tree
+    RouteConfig,
+}
+
+pub enum SourceMapOutput {
+    /// Write `<file>.map` next to each transformed file
+    NextToOutput,
+    /// Write `<dir>/<file relative to the app>.map`
+    Directory(String),
+}
+
+pub fn parse_args() -> Args {
+    let mut args = Args {
+        command: Command::Migrate,
+        dir: None,
+        source_map: None,
+        rewrite_imports: false,
+        data_hooks: DataHooks::default(),
+        route_imports: RouteImportMode::Rewrite,
+        resource_route: None,
+        definition_helpers: DefinitionHelpers::default(),
+        typed_links: false,
+    };
+
+    let mut iter = env::args().skip(1).peekable();
+    match iter.peek().map(|arg| arg.as_str()) {
+        Some("revert") => args.command = Command::Revert,
+        Some("typegen") => args.command = Command::Typegen,
+        Some("routes") => args.command = Command::RouteConfig,
+        _ => {}
+    }
+    if args.command != Command::Migrate {
+        iter.next();
+    }
+
+    while let Some(arg) = iter.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg.clone(), None),
+        };
+
+        match flag.as_str() {
+            "--source-map" => {
+                args.source_map = Some(SourceMapOutput::NextToOutput);
+            }
+            "--rewrite-imports" => {
+                args.rewrite_imports = true;
+            }
+            "--typed-links" => {
+                args.typed_links = true;
+            }
+            "--report-route-imports" => {
+                args.route_imports = RouteImportMode::Report;
+            }
+            "--source-map-dir" => {
+                let value = inline_value.or_else(|| iter.next()).unwrap_or_else(|| {
+                    eprintln!("Missing value for {}", flag);
+                    exit(1)
+                });
+                args.source_map = Some(SourceMapOutput::Directory(value));
+            }
+            "--resource-route" => {
+                let value = inline_value.or_else(|| iter.next()).unwrap_or_else(|| {
+                    eprintln!("Missing value for {}", flag);
+                    exit(1)
+                });
+                args.resource_route =
+                    Some(ResourceRouteForm::parse(&value).unwrap_or_else(|error| {
+                        eprintln!("{}", error);
+                        exit(1)
+                    }));
+            }
+            "--definition-helper" => {
+                let value = inline_value.or_else(|| iter.next()).unwrap_or_else(|| {
+                    eprintln!("Missing value for {}", flag);
+                    exit(1)
+                });
+                args.definition_helpers.add(&value);
+            }
+            "--preset" | "--hook" => {
+                let value = inline_value.or_else(|| iter.next()).unwrap_or_else(|| {
+                    eprintln!("Missing value for {}", flag);
+                    exit(1)
+                });
+                let result = if flag == "--preset" {
+                    args.data_hooks.add_preset(&value)
+                } else {
+                    args.data_hooks.add_hook_from_str(&value)
+                };
+                if let Err(error) = result {
+                    eprintln!("{}", error);
+                    exit(1)
+                }
+            }
+            _ if flag.starts_with("--") => {
+                eprintln!("Unknown option: {}", flag);
+                exit(1)
+            }
+            _ => {
+                if args.dir.is_some() {
+                    eprintln!("Unexpected argument: {}", arg);
+                    exit(1)
+                }
+                args.dir = Some(arg);
+            }
+        }
+    }
+
+    // `href()` is only exported by react-router
+    if args.typed_links && !args.rewrite_imports {
+        eprintln!("--typed-links requires --rewrite-imports");
+        exit(1)
+    }
+
+    args
+}
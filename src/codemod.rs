@@ -2,8 +2,9 @@ use oxc_allocator::Allocator;
 use oxc_ast::{
     ast::{
         AssignmentTarget, BindingPatternKind, Declaration, ExportDefaultDeclaration,
-        ExportDefaultDeclarationKind, ExportNamedDeclaration, Expression, FunctionBody, Statement,
-        VariableDeclaration,
+        ExportDefaultDeclarationKind, ExportNamedDeclaration, Expression, ExpressionStatement,
+        FormalParameters, FunctionBody, ObjectExpression, ObjectPropertyKind, Program, Statement,
+        TSType, TSTypeName, VariableDeclaration,
     },
     AstKind,
 };
@@ -14,13 +15,112 @@ use oxc_span::{GetSpan, SourceType, Span};
 use std::{cmp::Ordering, process, vec};
 
 use crate::{
-    codemod_models::{self, DefineRouteProperty, Method, StaticProperty},
+    codemod_models::{
+        self, CodemodError, CodemodOptions, CodemodOutput, DefineRouteProperty, Diagnostic, Method,
+        ResourceRouteForm, StaticProperty,
+    },
+    data_hooks::HookMatcher,
+    definition_helpers::DefinitionHelperMatcher,
+    export_specifiers::{
+        find_declaration, get_export_specifiers, get_movable_declaration, move_export_specifiers,
+    },
+    fetchers::type_fetchers,
     fixer::{Fix, Fixer},
+    function_semantics::{
+        get_declared_bindings, get_declared_name_span, get_export_move_strategy, get_move_strategy,
+        MoveStrategy,
+    },
+    imports::remove_named_imports,
+    line_endings::TextFormat,
+    meta_parents::get_meta_parents,
+    package_imports::rewrite_package_imports,
+    parent_routes::{type_route_loader_data, ParentRouteImports},
+    responses::unwrap_response_helpers,
+    sourcemap::{ByteMap, MappedText},
+    typed_links::type_links,
+    verify::verify,
 };
 
 use codemod_models::HookDeclarator;
 
-pub fn codemod(original_source_text: &String, source_type: SourceType) -> Result<String, ()> {
+/// Route module exports that move into defineRoute
+pub const KNOWN_REMIX_EXPORTS: [&str; 9] = [
+    "links",
+    "HydrateFallback",
+    "loader",
+    "clientLoader",
+    "action",
+    "clientAction",
+    "meta",
+    "ErrorBoundary",
+    "shouldRevalidate",
+];
+
+/// Route module exports only the root route has
+pub const ROOT_ROUTE_EXPORTS: [&str; 1] = ["Layout"];
+
+pub fn codemod(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
+    run_transform(source_text, source_type, options, transform)
+}
+
+/// Shared setup for whole-file transforms: line ending normalization,
+/// output verification and source map composition
+pub fn run_transform(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+    transform: impl Fn(&str, SourceType, &CodemodOptions) -> Result<CodemodOutput, CodemodError>,
+) -> Result<CodemodOutput, CodemodError> {
+    // Work on `\n` line endings without a BOM, and restore them at the end
+    let format = TextFormat::detect(source_text);
+    let normalized = format.normalize(source_text);
+
+    let output = transform(&normalized.text, source_type, options)?;
+    let diagnostics = output
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.locate(&normalized.text))
+        .collect();
+
+    // Keep the text byte for byte, line endings of unchanged files may be mixed
+    if !output.changed {
+        return Ok(CodemodOutput {
+            code: source_text.to_owned(),
+            source_map: output
+                .source_map
+                .map(|_| ByteMap::identity(source_text.len() as u32)),
+            diagnostics,
+            changed: false,
+        });
+    }
+
+    verify(
+        &normalized.text,
+        &output.code,
+        source_type,
+        &options.define_route_helpers(),
+    )?;
+    let restored = format.restore(&output.code);
+
+    Ok(CodemodOutput {
+        code: restored.text,
+        source_map: output
+            .source_map
+            .map(|map| restored.map.compose(&map).compose(&normalized.map)),
+        diagnostics,
+        changed: true,
+    })
+}
+
+fn transform(
+    original_source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
     //==========================================================================
     // First pass
     // : Clean up known remix exports - we don't want to include useLoaderData
@@ -29,26 +129,31 @@ pub fn codemod(original_source_text: &String, source_type: SourceType) -> Result
     //==========================================================================
 
     let allocator = Allocator::default();
-    let ret = Parser::new(&allocator, &original_source_text, source_type).parse();
+    let ret = Parser::new(&allocator, original_source_text, source_type).parse();
 
     if !ret.errors.is_empty() {
         for error in ret.errors {
-            let error = error.with_source_code(original_source_text.clone());
+            let error = error.with_source_code(original_source_text.to_string());
             println!("{error:?}");
         }
         process::exit(1);
     }
 
-    let semantic_ret = SemanticBuilder::new(&original_source_text, source_type)
+    let semantic_ret = SemanticBuilder::new(original_source_text, source_type)
         .with_trivias(ret.trivias)
         .build(&ret.program);
 
     let mut code_fixes = vec![];
     let mut route_module_properties = vec![];
     let mut hook_declarators: Vec<HookDeclarator> = vec![];
+    let hooks = HookMatcher::new(&ret.program, &options.data_hooks);
+    let definition_helpers =
+        DefinitionHelperMatcher::new(&ret.program, &options.definition_helpers);
+    // Named exports with args and their declarations
+    let mut exported_functions = vec![];
 
     // TODO: add headers
-    let known_remix_functions_with_args = vec![
+    let mut known_remix_functions_with_args = vec![
         "action",
         "clientAction",
         "clientLoader",
@@ -56,51 +161,291 @@ pub fn codemod(original_source_text: &String, source_type: SourceType) -> Result
         "meta",
         "shouldRevalidate",
     ];
+    if options.is_root_route {
+        known_remix_functions_with_args.extend(ROOT_ROUTE_EXPORTS);
+    }
+    let mut first_pass_diagnostics = vec![];
 
     for node in semantic_ret.semantic.nodes().iter() {
         match node.kind() {
             AstKind::ExportNamedDeclaration(named_export) => {
-                if let Some(name) = get_named_export_name(&node) {
+                if let Some(name) = get_named_export_name(node) {
+                    if name == "clientLoader" {
+                        code_fixes.extend(split_client_loader_assign(
+                            named_export,
+                            original_source_text,
+                        ));
+                    }
                     if known_remix_functions_with_args.contains(&name) {
-                        let type_annotations =
-                            get_named_export_function_args_type_annotations(&named_export);
-                        for span in type_annotations.iter() {
-                            code_fixes.push(Fix::delete(span.clone()));
+                        if let Some(declaration) = &named_export.declaration {
+                            exported_functions.push((name, declaration));
                         }
                     }
                 }
+                // `export { loader }`
+                for (name, local, _) in get_export_specifiers(named_export) {
+                    if named_export.source.is_some()
+                        || !known_remix_functions_with_args.contains(&name)
+                    {
+                        continue;
+                    }
+                    if let Some((_, declaration)) = find_declaration(&ret.program, local) {
+                        exported_functions.push((name, declaration));
+                    }
+                }
             }
             AstKind::ExportDefaultDeclaration(default_export) => {
                 match &default_export.declaration {
                     ExportDefaultDeclarationKind::FunctionDeclaration(decl) => {
                         if let Some(body) = &decl.body {
-                            get_hook_declarators(&body, &original_source_text)
+                            get_hook_declarators(body, original_source_text, &hooks)
                                 .iter()
                                 .for_each(|(hook, span)| {
-                                    code_fixes
-                                        .push(Fix::delete_with_leading_whitespace(span.clone()));
+                                    code_fixes.push(extract_hook_fix(hook, *span));
                                     hook_declarators.push(hook.clone());
                                 });
                         }
                     }
                     ExportDefaultDeclarationKind::ArrowFunctionExpression(decl) => {
-                        get_hook_declarators(&decl.body, &original_source_text)
+                        get_hook_declarators(&decl.body, original_source_text, &hooks)
                             .iter()
                             .for_each(|(hook, span)| {
-                                code_fixes.push(Fix::delete_with_leading_whitespace(span.clone()));
+                                code_fixes.push(extract_hook_fix(hook, *span));
                                 hook_declarators.push(hook.clone());
                             });
                     }
-                    _ => {}
+                    ExportDefaultDeclarationKind::Identifier(ident) => {
+                        // `export default Page`, if `Page` moves into defineRoute
+                        if let Some((_, body)) = get_movable_declaration(
+                            &ret.program,
+                            &semantic_ret.semantic,
+                            &ident.name,
+                            1,
+                        )
+                        .filter(|(_, declaration)| {
+                            get_move_strategy(declaration, &semantic_ret.semantic)
+                                == MoveStrategy::Method
+                        })
+                        .and_then(|(_, declaration)| get_declaration_function(declaration))
+                        {
+                            get_hook_declarators(body, original_source_text, &hooks)
+                                .iter()
+                                .for_each(|(hook, span)| {
+                                    code_fixes.push(extract_hook_fix(hook, *span));
+                                    hook_declarators.push(hook.clone());
+                                });
+                        }
+                    }
+                    _ => {
+                        // Partially migrated file, rewrite hooks added to the existing Component
+                        if let Some((params, body)) =
+                            get_define_route_object(default_export, &["defineRoute"])
+                                .and_then(get_define_route_component)
+                        {
+                            let hooks = get_hook_declarators(body, original_source_text, &hooks);
+                            for (hook, span) in hooks.iter() {
+                                code_fixes.push(extract_hook_fix(hook, *span));
+                            }
+                            let hooks = hooks.into_iter().map(|(hook, _)| hook).collect();
+                            if let Some(fix) =
+                                merge_component_params(params, &hooks, original_source_text)?
+                            {
+                                code_fixes.push(fix);
+                            }
+                        }
+                    }
                 }
             }
             _ => {}
         }
     }
 
-    let source_text = Fixer::new(&original_source_text, code_fixes)
-        .fix()
-        .fixed_code;
+    let moved_statements = get_moved_statements(&ret.program);
+
+    // `Layout` gets the same data args as `Component`, unless it stays a local
+    // binding that other code calls without them
+    for (name, declaration) in exported_functions.iter() {
+        if !ROOT_ROUTE_EXPORTS.contains(name)
+            || get_export_move_strategy(declaration, &semantic_ret.semantic, &moved_statements)
+                .is_local_binding()
+        {
+            continue;
+        }
+        let Some((params, body)) = get_declaration_function(declaration) else {
+            continue;
+        };
+        let layout_hooks = get_hook_declarators(body, original_source_text, &hooks);
+        for (hook, span) in layout_hooks.iter() {
+            code_fixes.push(extract_hook_fix(hook, *span));
+        }
+        let layout_hooks = layout_hooks
+            .into_iter()
+            .map(|(hook, _)| hook)
+            .collect::<Vec<_>>();
+        if layout_hooks.iter().any(|hook| hook.name == "loaderData") {
+            first_pass_diagnostics.push(Diagnostic::new(
+                "root-layout",
+                format!(
+                    "`{}` also renders around `ErrorBoundary`, where `loaderData` is undefined",
+                    name
+                ),
+                get_declared_name_span(declaration),
+            ));
+        }
+        if let Some(fix) = merge_component_params(params, &layout_hooks, original_source_text)? {
+            code_fixes.push(fix);
+        }
+    }
+
+    // Strip arg types of functions moving into defineRoute, where they are
+    // inferred. Hook calls and statements that reference them don't stay.
+    let mut removed = code_fixes
+        .iter()
+        .filter(|fix| fix.rule_id == Some("extract-hook"))
+        .map(|fix| fix.span)
+        .collect::<Vec<_>>();
+    removed.extend(moved_statements);
+    let mut parent_routes = ParentRouteImports::new(options, &semantic_ret.semantic);
+    // `useFetcher<typeof action>()` doesn't keep `action` a local binding
+    let moved_exports = exported_functions
+        .iter()
+        .filter(|(name, _)| ["loader", "action", "clientLoader", "clientAction"].contains(name))
+        .flat_map(|(_, declaration)| get_declared_bindings(declaration))
+        .filter_map(|binding| binding.symbol_id.get())
+        .collect::<Vec<_>>();
+    let fetchers = type_fetchers(
+        &ret.program,
+        &semantic_ret.semantic,
+        &moved_exports,
+        &mut parent_routes,
+    );
+    code_fixes.extend(fetchers.fixes);
+    first_pass_diagnostics.extend(fetchers.diagnostics);
+    removed.extend(fetchers.replaced);
+    // `MetaFunction<typeof loader, ...>` is inferred by defineRoute and its
+    // `parents`, so it doesn't keep `loader` a local binding
+    for (name, declaration) in exported_functions.iter() {
+        if *name != "meta"
+            || get_export_move_strategy(declaration, &semantic_ret.semantic, &removed)
+                .is_local_binding()
+        {
+            continue;
+        }
+        if let Some(span) = get_binding_type_annotation(declaration) {
+            code_fixes.push(Fix::delete(span).with_rule(
+                "strip-meta-type-annotation",
+                "Remove type annotation from `meta`",
+            ));
+            removed.push(span);
+        }
+    }
+    // Helper calls unwrapped per helper name
+    let mut unwrapped_helpers: Vec<(&str, usize)> = vec![];
+    let mut meta_parents = None;
+    for (name, declaration) in exported_functions {
+        if get_export_move_strategy(declaration, &semantic_ret.semantic, &removed)
+            .is_local_binding()
+        {
+            continue;
+        }
+        if name == "meta" {
+            meta_parents = get_meta_parents(
+                declaration,
+                original_source_text,
+                &mut parent_routes,
+                &mut first_pass_diagnostics,
+            );
+        }
+        for span in get_function_args_type_annotations(declaration, &definition_helpers).iter() {
+            code_fixes.push(Fix::delete(*span).with_rule(
+                "strip-args-type-annotation",
+                format!("Remove type annotation from `{}` arguments", name),
+            ));
+        }
+        // `unstable_defineLoader(async () => ...)` only adds types that
+        // defineRoute infers too
+        let Declaration::VariableDeclaration(decl) = declaration else {
+            continue;
+        };
+        for init in decl.declarations.iter().filter_map(|d| d.init.as_ref()) {
+            let (Some(func), Some(callee)) = (
+                definition_helpers.unwrap(init),
+                definition_helpers.get_callee(init),
+            ) else {
+                continue;
+            };
+            let message = format!("Unwrap `{}` from `{}`", callee, name);
+            code_fixes.push(
+                Fix::delete(Span::new(init.span().start, func.span().start))
+                    .with_rule("unwrap-definition-helper", message.clone()),
+            );
+            code_fixes.push(
+                Fix::delete(Span::new(func.span().end, init.span().end))
+                    .with_rule("unwrap-definition-helper", message),
+            );
+            match unwrapped_helpers.iter_mut().find(|(c, _)| *c == callee) {
+                Some((_, count)) => *count += 1,
+                None => unwrapped_helpers.push((callee, 1)),
+            }
+        }
+    }
+    // Drop imports of helpers that have no calls left
+    let unused_helpers = unwrapped_helpers
+        .iter()
+        .filter(|(callee, count)| {
+            semantic_ret
+                .semantic
+                .scopes()
+                .get_root_binding(callee)
+                .is_some_and(|symbol_id| {
+                    semantic_ret
+                        .semantic
+                        .symbols()
+                        .get_resolved_reference_ids(symbol_id)
+                        .len()
+                        == *count
+                })
+        })
+        .map(|(callee, _)| *callee)
+        .collect::<Vec<_>>();
+    if !unused_helpers.is_empty() {
+        code_fixes.extend(remove_named_imports(
+            &ret.program,
+            original_source_text,
+            &unused_helpers,
+        ));
+    }
+
+    let (route_loader_data_fixes, route_loader_data_diagnostics) =
+        type_route_loader_data(&ret.program, &semantic_ret.semantic, &mut parent_routes);
+    code_fixes.extend(route_loader_data_fixes);
+    first_pass_diagnostics.extend(route_loader_data_diagnostics);
+    code_fixes.extend(parent_routes.into_fixes(&ret.program, original_source_text));
+    if options.typed_links && options.rewrite_imports {
+        let (link_fixes, link_diagnostics) = type_links(
+            &ret.program,
+            &semantic_ret.semantic,
+            original_source_text,
+            &options.routes,
+        );
+        code_fixes.extend(link_fixes);
+        first_pass_diagnostics.extend(link_diagnostics);
+    }
+
+    // Responses are the point of resource routes
+    let is_resource_route = options.resource_route.is_some() && is_resource_route(&ret.program);
+    if !is_resource_route {
+        code_fixes.extend(unwrap_response_helpers(
+            &ret.program,
+            &semantic_ret.semantic,
+            original_source_text,
+            &options.data_hooks,
+            options.rewrite_imports,
+        ));
+    }
+
+    // Diagnostics of the second pass are mapped back to the original text
+    let (source_text, first_pass_map) = apply_fixes(original_source_text, code_fixes, true)?;
 
     //==========================================================================
     // Second pass
@@ -116,94 +461,631 @@ pub fn codemod(original_source_text: &String, source_type: SourceType) -> Result
         .build(&ret.program);
 
     // TODO: There are also `headers` and `handle`
-    let known_remix_exports = vec![
-        "links",
-        "HydrateFallback",
-        "loader",
-        "clientLoader",
-        "action",
-        "clientAction",
-        "meta",
-        "ErrorBoundary",
-        "shouldRevalidate",
-    ];
+    let mut known_remix_exports = KNOWN_REMIX_EXPORTS.to_vec();
+    if options.is_root_route {
+        known_remix_exports.extend(ROOT_ROUTE_EXPORTS);
+    }
 
     let mut code_fixes = vec![];
+    let mut existing_route_object = None;
+
+    let (import_fixes, mut diagnostics) = if options.rewrite_imports {
+        let (fixes, diagnostics) = rewrite_package_imports(&ret.program, &source_text);
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| map_diagnostic(diagnostic, &first_pass_map))
+            .collect();
+        (fixes, diagnostics)
+    } else {
+        (vec![], vec![])
+    };
+    diagnostics.extend(first_pass_diagnostics);
+
+    let moved_statements = get_moved_statements(&ret.program);
+    let define_route_helpers = options.define_route_helpers();
 
     for node in semantic_ret.semantic.nodes().iter() {
         match node.kind() {
             AstKind::ExportNamedDeclaration(named_export) => {
-                if let Some(name) = get_named_export_name(&node) {
+                if let Some(name) = get_named_export_name(node) {
                     if known_remix_exports.contains(&name) {
-                        let property = get_named_export_property(&named_export, &source_text);
+                        let key = rename_exports(Some(name)).unwrap_or("$");
+                        let Some(declaration) = &named_export.declaration else {
+                            continue;
+                        };
+                        let strategy = get_export_move_strategy(
+                            declaration,
+                            &semantic_ret.semantic,
+                            &moved_statements,
+                        );
+                        let name_span = get_declared_name_span(declaration);
+                        if let Some(diagnostic) = strategy.diagnostic(name, name_span) {
+                            diagnostics.push(map_diagnostic(diagnostic, &first_pass_map));
+                        }
+
+                        if strategy.is_local_binding() {
+                            // `export const loader = ...` stays as `const loader = ...`
+                            route_module_properties.push(
+                                DefineRouteProperty::StaticProperty(StaticProperty {
+                                    key,
+                                    value: name_span.source_text(&source_text),
+                                    span: name_span,
+                                })
+                                .default_name(name),
+                            );
+                            code_fixes.push(
+                                Fix::delete(Span::new(
+                                    named_export.span.start,
+                                    declaration.span().start,
+                                ))
+                                .with_rule(
+                                    "move-export",
+                                    format!("Reference `{}` in defineRoute", name),
+                                ),
+                            );
+                            continue;
+                        }
+
+                        let property = get_moved_declaration_property(
+                            declaration,
+                            name,
+                            key,
+                            &source_text,
+                            &strategy,
+                        );
                         if let Some(p) = property {
                             route_module_properties.push(p.default_name(name));
                         }
-                        code_fixes.push(Fix::delete_with_leading_whitespace(named_export.span));
+                        code_fixes.push(
+                            Fix::delete_with_leading_whitespace(named_export.span).with_rule(
+                                "move-export",
+                                format!("Move `{}` export into defineRoute", name),
+                            ),
+                        );
+                    } else if ROOT_ROUTE_EXPORTS.contains(&name) {
+                        diagnostics.push(map_diagnostic(
+                            Diagnostic::new(
+                                "root-layout",
+                                format!(
+                                    "`{}` is only used in the root route, it was left as an export",
+                                    name
+                                ),
+                                named_export.span,
+                            ),
+                            &first_pass_map,
+                        ));
                     }
+                } else {
+                    let moved = move_export_specifiers(
+                        named_export,
+                        &ret.program,
+                        &semantic_ret.semantic,
+                        &source_text,
+                        &known_remix_exports,
+                    );
+                    route_module_properties.extend(moved.properties);
+                    code_fixes.extend(moved.fixes);
+                    diagnostics.extend(
+                        moved
+                            .diagnostics
+                            .into_iter()
+                            .map(|diagnostic| map_diagnostic(diagnostic, &first_pass_map)),
+                    );
                 }
             }
             AstKind::ExportDefaultDeclaration(default_export) => {
-                if is_new_module_default_export(&node) {
-                    println!("File already has a new module default export");
-                    return Err(());
+                if is_new_module_default_export(node, &define_route_helpers) {
+                    // Leftover classic exports get merged into the existing object
+                    match get_define_route_object(default_export, &define_route_helpers) {
+                        Some(object) => {
+                            existing_route_object = Some(object);
+                            continue;
+                        }
+                        None => return Err(CodemodError::AlreadyMigrated),
+                    }
                 }
-                let property = get_default_export_property(&default_export, &source_text);
+                let property = match &default_export.declaration {
+                    ExportDefaultDeclarationKind::Identifier(ident) => {
+                        let declaration = get_movable_declaration(
+                            &ret.program,
+                            &semantic_ret.semantic,
+                            &ident.name,
+                            1,
+                        );
+                        let strategy = declaration.map(|(_, declaration)| {
+                            get_move_strategy(declaration, &semantic_ret.semantic)
+                        });
+                        if let Some(reason) = strategy.as_ref().and_then(MoveStrategy::reason) {
+                            diagnostics.push(map_diagnostic(
+                                Diagnostic::new(
+                                    "preserve-function-semantics",
+                                    format!(
+                                        "`{}` {}, so it was kept as a local binding",
+                                        ident.name, reason
+                                    ),
+                                    ident.span,
+                                ),
+                                &first_pass_map,
+                            ));
+                        }
+                        let moved = declaration
+                            .filter(|_| strategy == Some(MoveStrategy::Method))
+                            .and_then(|(statement, declaration)| {
+                                get_declaration_property(declaration, "$", &source_text)
+                                    .map(|property| (statement.span(), property))
+                            });
+                        match moved {
+                            Some((statement, property)) => {
+                                code_fixes.push(
+                                    Fix::delete_with_leading_whitespace(statement).with_rule(
+                                        "move-default-export",
+                                        format!("Move `{}` into defineRoute", ident.name),
+                                    ),
+                                );
+                                Some(property)
+                            }
+                            // Used elsewhere in the file, or can't be a method
+                            None => {
+                                if has_hook_declarators(
+                                    &ret.program,
+                                    &ident.name,
+                                    &source_text,
+                                    &hooks,
+                                ) {
+                                    diagnostics.push(map_diagnostic(
+                                        Diagnostic::new(
+                                            "move-default-export",
+                                            format!(
+                                                "`{}` stays a local binding, so its data hooks weren't moved to component args",
+                                                ident.name
+                                            ),
+                                            ident.span,
+                                        ),
+                                        &first_pass_map,
+                                    ));
+                                }
+                                Some(DefineRouteProperty::StaticProperty(StaticProperty {
+                                    key: "$",
+                                    value: ident.span.source_text(&source_text),
+                                    span: ident.span,
+                                }))
+                            }
+                        }
+                    }
+                    // Wrapped components, like `memo(Page)` or `withErrorBoundary(Page)`
+                    ExportDefaultDeclarationKind::CallExpression(call_expr) => {
+                        diagnostics.push(map_diagnostic(
+                            Diagnostic::new(
+                                "move-default-export",
+                                format!(
+                                    "Component is wrapped in `{}()`, data hooks inside it weren't moved to component args",
+                                    call_expr.callee.span().source_text(&source_text)
+                                ),
+                                call_expr.span,
+                            ),
+                            &first_pass_map,
+                        ));
+                        Some(DefineRouteProperty::StaticProperty(StaticProperty {
+                            key: "$",
+                            value: call_expr.span.source_text(&source_text),
+                            span: call_expr.span,
+                        }))
+                    }
+                    _ => get_default_export_property(default_export, &source_text),
+                };
                 if let Some(p) = property {
                     route_module_properties.push(
                         p.default_name("Component")
                             .set_args(construct_component_params(&hook_declarators)),
                     );
                 }
-                code_fixes.push(Fix::delete_with_leading_whitespace(default_export.span));
+                code_fixes.push(
+                    Fix::delete_with_leading_whitespace(default_export.span).with_rule(
+                        "move-default-export",
+                        "Move default export into defineRoute as `Component`",
+                    ),
+                );
             }
             AstKind::ExpressionStatement(expr_stmt) => {
-                if let Expression::AssignmentExpression(assignment_expr) = &expr_stmt.expression {
-                    if matches!(
-                        &assignment_expr.left,
-                        AssignmentTarget::StaticMemberExpression(member_expr)
-                            if matches!(&member_expr.object, Expression::Identifier(ident) if ident.name == "clientLoader")
-                            && member_expr.property.name == "hydrate"
-                    ) {
-                        let value = assignment_expr.right.span().source_text(&source_text);
-                        route_module_properties.push(DefineRouteProperty::StaticProperty(
-                            StaticProperty {
-                                key: "clientLoaderHydrate",
-                                value,
-                            },
-                        ));
-                        code_fixes.push(Fix::delete_with_leading_whitespace(expr_stmt.span));
-                    }
+                if let Some(value) = get_client_loader_hydrate(expr_stmt) {
+                    let span = value.span();
+                    route_module_properties.push(DefineRouteProperty::StaticProperty(
+                        StaticProperty {
+                            key: "clientLoaderHydrate",
+                            value: span.source_text(&source_text),
+                            span,
+                        },
+                    ));
+                    code_fixes.push(
+                        Fix::delete_with_leading_whitespace(expr_stmt.span).with_rule(
+                            "move-client-loader-hydrate",
+                            "Move `clientLoader.hydrate` into defineRoute",
+                        ),
+                    );
                 }
             }
             _ => {}
         }
     }
 
-    // If there are no known remix exports, return the original source text.
-    // It's fine that this check is after the second pass, as most route files
-    // will have at least one known remix export.
-    if route_module_properties.len() == 0 {
-        return Ok(original_source_text.to_string());
+    diagnostics.extend(
+        check_hydrate_fallback(&route_module_properties, existing_route_object)
+            .into_iter()
+            .map(|diagnostic| map_diagnostic(diagnostic, &first_pass_map)),
+    );
+
+    if route_module_properties.is_empty() {
+        // No known remix exports, keep everything but the rewritten imports
+        code_fixes.clear();
+    } else if let Some(object) = existing_route_object {
+        if meta_parents.is_some() {
+            diagnostics.push(map_diagnostic(
+                Diagnostic::new(
+                    "meta-parents",
+                    "Add the imported parent routes of `meta` to `parents` of the existing defineRoute"
+                        .to_owned(),
+                    object.span,
+                ),
+                &first_pass_map,
+            ));
+        }
+        for fix in
+            merge_route_object_properties(object, &mut route_module_properties, &source_text)?
+        {
+            code_fixes.push(fix.with_rule(
+                "merge-define-route",
+                "Merge exports into existing defineRoute",
+            ));
+        }
+    } else {
+        let new_export_position = source_text.len() as u32;
+
+        code_fixes.push(
+            Fix::insert_mapped(
+                construct_new_module_object(
+                    &mut route_module_properties,
+                    options
+                        .resource_route
+                        .as_ref()
+                        .filter(|_| is_resource_route),
+                    meta_parents.as_deref(),
+                ),
+                Span::new(new_export_position, new_export_position),
+            )
+            .with_rule("insert-define-route", "Insert defineRoute default export"),
+        );
+    }
+    code_fixes.extend(import_fixes);
+
+    let (code, second_pass_map) = apply_fixes(&source_text, code_fixes, options.source_map)?;
+
+    Ok(CodemodOutput {
+        changed: code != original_source_text,
+        code,
+        source_map: second_pass_map
+            .zip(first_pass_map)
+            .map(|(second, first)| second.compose(&first)),
+        diagnostics,
+    })
+}
+
+/// `HydrateFallback` only renders while a client loader runs on hydration,
+/// which it does when `clientLoader.hydrate` is set or there's no server loader
+fn check_hydrate_fallback(
+    properties: &[DefineRouteProperty],
+    existing_route_object: Option<&ObjectExpression>,
+) -> Vec<Diagnostic> {
+    let existing_keys = existing_route_object
+        .iter()
+        .flat_map(|object| object.properties.iter())
+        .filter_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(p) => p.key.static_name().map(|k| k.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let has_key = |key: &str| {
+        properties.iter().any(|p| p.key() == key) || existing_keys.iter().any(|k| k == key)
+    };
+    let find = |key: &str| properties.iter().find(|p| p.key() == key);
+
+    let sets_hydrate = match find("clientLoaderHydrate") {
+        Some(DefineRouteProperty::StaticProperty(p)) => p.value.trim() != "false",
+        Some(DefineRouteProperty::Method(_)) => true,
+        None => existing_keys.iter().any(|k| k == "clientLoaderHydrate"),
+    };
+    let hydrates = sets_hydrate || (has_key("clientLoader") && !has_key("serverLoader"));
+
+    let mut diagnostics = vec![];
+    if let Some(hydrate) = find("clientLoaderHydrate") {
+        if sets_hydrate && !has_key("HydrateFallback") {
+            diagnostics.push(Diagnostic::new(
+                "hydrate-fallback",
+                "`clientLoader.hydrate` is set without a `HydrateFallback` to render until the client loader finishes".to_owned(),
+                hydrate.span(),
+            ));
+        }
+    }
+    if let Some(fallback) = find("HydrateFallback") {
+        if !hydrates {
+            diagnostics.push(Diagnostic::new(
+                "hydrate-fallback",
+                "`HydrateFallback` is never rendered, there's no `clientLoader` that runs on hydration".to_owned(),
+                fallback.span(),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Route modules without a component, like API endpoints and feeds
+fn is_resource_route(program: &Program) -> bool {
+    let component_exports = ["default", "ErrorBoundary", "HydrateFallback", "Layout"];
+    program.body.iter().all(|statement| match statement {
+        Statement::ExportDefaultDeclaration(_) => false,
+        Statement::ExportNamedDeclaration(named_export) => {
+            let declared = named_export
+                .declaration
+                .iter()
+                .flat_map(get_declared_bindings)
+                .map(|binding| binding.name.as_str());
+            let specified = named_export
+                .specifiers
+                .iter()
+                .map(|specifier| specifier.exported.name().as_str());
+            !declared
+                .chain(specified)
+                .any(|name| component_exports.contains(&name))
+        }
+        _ => true,
+    })
+}
+
+/// Value of a `clientLoader.hydrate = value` or
+/// `Object.assign(clientLoader, { hydrate: value })` statement
+fn get_client_loader_hydrate<'a, 'b>(
+    expr_stmt: &'b ExpressionStatement<'a>,
+) -> Option<&'b Expression<'a>> {
+    let value = match &expr_stmt.expression {
+        Expression::AssignmentExpression(assignment_expr) => matches!(
+            &assignment_expr.left,
+            AssignmentTarget::StaticMemberExpression(member_expr)
+                if matches!(&member_expr.object, Expression::Identifier(ident) if ident.name == "clientLoader")
+                && member_expr.property.name == "hydrate"
+        )
+        .then_some(&assignment_expr.right),
+        expr => get_hydrate_assign(expr)
+            .filter(|(target, _)| target.is_specific_id("clientLoader"))
+            .map(|(_, value)| value),
+    }?;
+    Some(without_as_const(value))
+}
+
+/// Target and value of `Object.assign(target, { hydrate: value })`
+fn get_hydrate_assign<'a, 'b>(
+    expr: &'b Expression<'a>,
+) -> Option<(&'b Expression<'a>, &'b Expression<'a>)> {
+    let Expression::CallExpression(call_expr) = expr else {
+        return None;
+    };
+    if !call_expr
+        .callee
+        .is_specific_member_access("Object", "assign")
+        || call_expr.arguments.len() != 2
+    {
+        return None;
+    }
+    let target = call_expr.arguments[0].as_expression()?;
+    let Some(Expression::ObjectExpression(object)) = call_expr.arguments[1].as_expression() else {
+        return None;
+    };
+    match object.properties.as_slice() {
+        [ObjectPropertyKind::ObjectProperty(property)]
+            if !property.computed && property.key.is_specific_static_name("hydrate") =>
+        {
+            Some((target, &property.value))
+        }
+        _ => None,
+    }
+}
+
+/// `true` for `true as const`, which is only needed on exports
+fn without_as_const<'a, 'b>(expr: &'b Expression<'a>) -> &'b Expression<'a> {
+    match expr {
+        Expression::TSAsExpression(as_expr)
+            if matches!(
+                &as_expr.type_annotation,
+                TSType::TSTypeReference(reference)
+                    if matches!(&reference.type_name, TSTypeName::IdentifierReference(ident) if ident.name == "const")
+            ) =>
+        {
+            &as_expr.expression
+        }
+        _ => expr,
+    }
+}
+
+/// Turn `export const clientLoader = Object.assign(fn, { hydrate })` into
+/// `export const clientLoader = fn` followed by `clientLoader.hydrate = hydrate`,
+/// which moves into defineRoute like any other
+fn split_client_loader_assign<'a>(
+    named_export: &ExportNamedDeclaration,
+    source_text: &str,
+) -> Vec<Fix<'a>> {
+    let Some(Declaration::VariableDeclaration(decl)) = &named_export.declaration else {
+        return vec![];
+    };
+    let Some((init, (target, value))) = decl.declarations.first().and_then(|declarator| {
+        let init = declarator.init.as_ref()?;
+        Some((init, get_hydrate_assign(init)?))
+    }) else {
+        return vec![];
+    };
+    let after_init = Span::new(init.span().end, named_export.span.end);
+    vec![
+        Fix::delete(Span::new(init.span().start, target.span().start)),
+        Fix::insert(
+            format!(
+                "{}\nclientLoader.hydrate = {};",
+                after_init.source_text(source_text),
+                without_as_const(value).span().source_text(source_text)
+            ),
+            Span::new(target.span().end, named_export.span.end),
+        ),
+    ]
+    .into_iter()
+    .map(|fix| {
+        fix.with_rule(
+            "split-client-loader-hydrate",
+            "Assign `clientLoader.hydrate` separately",
+        )
+    })
+    .collect()
+}
+
+/// Statements that move into defineRoute along with the exports they reference
+pub fn get_moved_statements(program: &Program) -> Vec<Span> {
+    program
+        .body
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::ExpressionStatement(expr_stmt) => {
+                get_client_loader_hydrate(expr_stmt).map(|_| expr_stmt.span)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Point a second pass diagnostic at the original text
+pub fn map_diagnostic(diagnostic: Diagnostic, first_pass_map: &Option<ByteMap>) -> Diagnostic {
+    match first_pass_map {
+        Some(map) => Diagnostic {
+            span: map.original_span(diagnostic.span),
+            ..diagnostic
+        },
+        None => diagnostic,
+    }
+}
+
+/// Apply all fixes, or fail if any of them couldn't be applied
+pub fn apply_fixes<'a>(
+    source_text: &'a str,
+    fixes: Vec<Fix<'a>>,
+    source_map: bool,
+) -> Result<(String, Option<ByteMap>), CodemodError> {
+    let result = Fixer::new(source_text, fixes)
+        .with_source_map(source_map)
+        .fix();
+
+    if !result.dropped.is_empty() {
+        return Err(CodemodError::DroppedFixes(result.dropped));
+    }
+
+    Ok((result.fixed_code.into_owned(), result.source_map))
+}
+
+pub fn extract_hook_fix<'a>(hook: &HookDeclarator, span: Span) -> Fix<'a> {
+    Fix::delete_with_leading_whitespace(span).with_rule(
+        "extract-hook",
+        format!("Move `{}` hook call into component arguments", hook.name),
+    )
+}
+
+fn construct_new_module_object(
+    properties: &mut Vec<DefineRouteProperty>,
+    resource_route: Option<&ResourceRouteForm>,
+    parents: Option<&str>,
+) -> MappedText {
+    let mut new_module = MappedText::default();
+    match resource_route {
+        Some(ResourceRouteForm::Helper(name)) => {
+            new_module.push_str(&format!("\nexport default {}({{\n", name));
+        }
+        Some(ResourceRouteForm::Flag) => {
+            new_module.push_str("\nexport default defineRoute({\n  resource: true,\n");
+        }
+        None => new_module.push_str("\nexport default defineRoute({\n"),
+    }
+    if let Some(parents) = parents {
+        new_module.push_str(&format!("  parents: {},\n", parents));
+    }
+    new_module.push_mapped(&construct_route_properties(properties).indent_all_by(2));
+    new_module.push_str("});\n");
+
+    new_module
+}
+
+/// Insert properties at the end of an existing `defineRoute({ ... })` object
+fn merge_route_object_properties<'a>(
+    object: &ObjectExpression,
+    properties: &mut Vec<DefineRouteProperty>,
+    source_text: &str,
+) -> Result<Vec<Fix<'a>>, CodemodError> {
+    let existing_keys = object
+        .properties
+        .iter()
+        .filter_map(|p| match p {
+            ObjectPropertyKind::ObjectProperty(p) => p.key.static_name(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let conflicts = properties
+        .iter()
+        .filter(|p| existing_keys.iter().any(|key| key.as_str() == p.key()))
+        .map(|p| p.key().to_owned())
+        .collect::<Vec<_>>();
+    if !conflicts.is_empty() {
+        return Err(CodemodError::PropertyConflicts(conflicts));
     }
 
-    let new_export_position = source_text.len() as u32;
+    let mut fixes = vec![];
+    let closing_brace = object.span.end - 1;
 
-    code_fixes.push(Fix::insert(
-        construct_new_module_object(&mut route_module_properties),
-        Span::new(new_export_position, new_export_position),
-    ));
+    let (mut position, indentation) = match object.properties.last() {
+        Some(last) => {
+            let mut position = last.span().end;
+            let after_last = &source_text[position as usize..closing_brace as usize];
+            let trimmed = after_last.trim_start();
+            if trimmed.starts_with(',') {
+                position += (after_last.len() - trimmed.len()) as u32 + 1;
+            } else {
+                fixes.push(Fix::insert(",", Span::new(position, position)));
+            }
+            let first = object.properties[0].span().start;
+            (position, get_indentation(source_text, first))
+        }
+        None => (object.span.start + 1, 2),
+    };
+
+    let mut content = MappedText::default();
+    let rest = &source_text[position as usize..closing_brace as usize];
+    match rest.find('\n') {
+        // Insert on its own lines, right before the line with the closing brace
+        Some(newline) => position += newline as u32 + 1,
+        None => content.push_str("\n"),
+    }
+    content.push_mapped(&construct_route_properties(properties).indent_all_by(indentation));
+    fixes.push(Fix::insert_mapped(content, Span::new(position, position)));
 
-    let fixed_code = Fixer::new(&source_text, code_fixes)
-        .fix()
-        .fixed_code
-        .to_string();
+    Ok(fixes)
+}
 
-    Ok(fixed_code)
+/// Number of spaces before the given position, if it's the first thing on its line
+pub fn get_indentation(source_text: &str, position: u32) -> usize {
+    let line_start = source_text[..position as usize]
+        .rfind('\n')
+        .map_or(0, |pos| pos + 1);
+    let before = &source_text[line_start..position as usize];
+    if before.chars().all(|c| c == ' ') {
+        before.len()
+    } else {
+        2
+    }
 }
 
-fn construct_new_module_object<'a>(properties: &mut Vec<DefineRouteProperty>) -> String {
-    let mut module_object = String::from("export default defineRoute({\n");
+fn construct_route_properties(properties: &mut Vec<DefineRouteProperty>) -> MappedText {
+    let mut module_object = MappedText::default();
 
     // module_object.push_str("  params: [],\n");
 
@@ -211,7 +1093,7 @@ fn construct_new_module_object<'a>(properties: &mut Vec<DefineRouteProperty>) ->
     // TODO: sort by predefined order, as in `known_remix_exports`
     properties.sort_by(|a, b| match (a, b) {
         (DefineRouteProperty::StaticProperty(a), DefineRouteProperty::StaticProperty(b)) => {
-            a.key.cmp(&b.key)
+            a.key.cmp(b.key)
         }
         (DefineRouteProperty::Method(a), DefineRouteProperty::Method(b)) => a.span.cmp(&b.span),
         (DefineRouteProperty::StaticProperty(_), DefineRouteProperty::Method(_)) => {
@@ -222,34 +1104,51 @@ fn construct_new_module_object<'a>(properties: &mut Vec<DefineRouteProperty>) ->
 
     for export in properties.iter() {
         match export {
+            // Shorthand for bindings with the same name, like `meta: meta`
+            DefineRouteProperty::StaticProperty(static_prop)
+                if static_prop.key == static_prop.value =>
+            {
+                module_object.push_source(static_prop.value, static_prop.span.start);
+                module_object.push_str(",\n");
+            }
             DefineRouteProperty::StaticProperty(static_prop) => {
-                module_object.push_str(&format!("{}: {},\n", static_prop.key, static_prop.value));
+                module_object.push_str(&format!("{}: ", static_prop.key));
+                module_object.push_source(static_prop.value, static_prop.span.start);
+                module_object.push_str(",\n");
             }
             DefineRouteProperty::Method(method) => {
                 module_object.push_str(&format!(
-                    "{}{}({}) {},\n",
+                    "{}{}(",
                     if method.is_async { "async " } else { "" },
                     method.key,
-                    method.args,
-                    method.body,
                 ));
+                match method.args_span {
+                    Some(span) => module_object.push_source(&method.args, span.start),
+                    None => module_object.push_str(&method.args),
+                }
+                module_object.push_str(") ");
+                // Bodies of nested functions, like in `Object.assign(fn, { ... })`,
+                // are indented further than their closing brace
+                let closing_indentation = method.body.rsplit_once('\n').map_or(0, |(_, last)| {
+                    last.len() - last.trim_start_matches(' ').len()
+                });
+                let mut body = MappedText::default();
+                body.push_source(method.body, method.body_span.start);
+                module_object.push_mapped(&body.dedent_by(closing_indentation));
+                module_object.push_str(",\n");
             }
         }
     }
 
-    module_object = indent::indent_all_by(2, module_object);
-
-    module_object.push_str("});\n");
-
-    format!("\n{}", module_object.trim_start().to_owned())
+    module_object
 }
 
-fn is_new_module_default_export(node: &AstNode) -> bool {
+fn is_new_module_default_export(node: &AstNode, helpers: &[&str]) -> bool {
     if let AstKind::ExportDefaultDeclaration(default_export) = node.kind() {
         if let ExportDefaultDeclarationKind::CallExpression(call_expr) = &default_export.declaration
         {
             if let Expression::Identifier(ident) = &call_expr.callee {
-                return ident.name == "defineRoute";
+                return helpers.contains(&ident.name.as_str());
             }
         }
     }
@@ -257,7 +1156,7 @@ fn is_new_module_default_export(node: &AstNode) -> bool {
     false
 }
 
-fn get_named_export_name<'a>(node: &'a AstNode<'a>) -> Option<&'a str> {
+pub fn get_named_export_name<'a>(node: &'a AstNode<'a>) -> Option<&'a str> {
     match node.kind() {
         AstKind::ExportNamedDeclaration(named_export) => {
             if let Some(Declaration::FunctionDeclaration(decl)) = &named_export.declaration {
@@ -278,28 +1177,38 @@ fn get_named_export_name<'a>(node: &'a AstNode<'a>) -> Option<&'a str> {
     }
 }
 
-fn get_named_export_function_args_type_annotations<'a>(
-    named_export: &'a ExportNamedDeclaration<'a>,
+pub fn get_function_args_type_annotations(
+    declaration: &Declaration,
+    definition_helpers: &DefinitionHelperMatcher,
 ) -> Vec<Span> {
     let mut type_annotations = vec![];
 
-    match &named_export.declaration {
-        Some(Declaration::FunctionDeclaration(decl)) => {
+    match declaration {
+        Declaration::FunctionDeclaration(decl) => {
             decl.params.items.iter().for_each(|param| {
                 if let Some(type_annotation) = &param.pattern.type_annotation {
                     type_annotations.push(type_annotation.span);
                 }
             });
         }
-        Some(Declaration::VariableDeclaration(decl)) => {
+        Declaration::VariableDeclaration(decl) => {
             decl.declarations.iter().for_each(|d| {
-                if let Some(Expression::ArrowFunctionExpression(arrow_func)) = &d.init {
-                    arrow_func.params.items.iter().for_each(|param| {
-                        if let Some(type_annotation) = &param.pattern.type_annotation {
-                            type_annotations.push(type_annotation.span);
-                        }
-                    });
-                }
+                // `Object.assign(fn, { hydrate: true })`, `unstable_defineLoader(fn)`
+                let init = d
+                    .init
+                    .as_ref()
+                    .map(|init| get_hydrate_assign(init).map_or(init, |(target, _)| target))
+                    .map(|init| definition_helpers.unwrap(init).unwrap_or(init));
+                let params = match init {
+                    Some(Expression::ArrowFunctionExpression(arrow_func)) => &arrow_func.params,
+                    Some(Expression::FunctionExpression(func)) => &func.params,
+                    _ => return,
+                };
+                params.items.iter().for_each(|param| {
+                    if let Some(type_annotation) = &param.pattern.type_annotation {
+                        type_annotations.push(type_annotation.span);
+                    }
+                });
             });
         }
         _ => {}
@@ -308,27 +1217,79 @@ fn get_named_export_function_args_type_annotations<'a>(
     type_annotations
 }
 
-fn get_named_export_property<'a>(
-    node: &ExportNamedDeclaration<'a>,
+/// Span of the type annotation of `export const name: Type = ...`
+fn get_binding_type_annotation(declaration: &Declaration) -> Option<Span> {
+    match declaration {
+        Declaration::VariableDeclaration(decl) if decl.declarations.len() == 1 => decl.declarations
+            [0]
+        .id
+        .type_annotation
+        .as_ref()
+        .map(|type_annotation| type_annotation.span),
+        _ => None,
+    }
+}
+
+/// Property for the declaration of `name` moving into defineRoute, as a method
+/// unless that would change what the function does. `None` for local bindings.
+pub fn get_moved_declaration_property<'a>(
+    declaration: &Declaration<'a>,
+    name: &str,
+    key: &'a str,
     source_text: &'a str,
+    strategy: &MoveStrategy,
 ) -> Option<DefineRouteProperty<'a>> {
-    match &node.declaration {
-        Some(Declaration::FunctionDeclaration(decl)) => {
+    match strategy {
+        MoveStrategy::Method => get_declaration_property(declaration, key, source_text),
+        MoveStrategy::Function(_) => {
+            let span = match declaration {
+                Declaration::FunctionDeclaration(decl) => decl.span,
+                Declaration::VariableDeclaration(decl) => decl
+                    .declarations
+                    .iter()
+                    .find(|declarator| {
+                        matches!(&declarator.id.kind, BindingPatternKind::BindingIdentifier(ident) if ident.name == name)
+                    })?
+                    .init
+                    .as_ref()?
+                    .span(),
+                _ => return None,
+            };
+            Some(DefineRouteProperty::StaticProperty(StaticProperty {
+                key,
+                value: span.source_text(source_text),
+                span,
+            }))
+        }
+        MoveStrategy::LocalBinding(_) | MoveStrategy::UsedElsewhere => None,
+    }
+}
+
+/// Property for a function declaration, or a variable declaration with a
+/// function, with `key` as the property name
+pub fn get_declaration_property<'a>(
+    declaration: &Declaration<'a>,
+    key: &'a str,
+    source_text: &'a str,
+) -> Option<DefineRouteProperty<'a>> {
+    match declaration {
+        Declaration::FunctionDeclaration(decl) => {
             if let Some(body) = &decl.body {
                 return Some(DefineRouteProperty::Method(Method {
-                    key: rename_exports(decl.id.as_ref().map(|id| id.name.as_str())).unwrap_or("$"),
+                    key,
                     span: decl.span,
-                    // remove the parentheses
-                    args: Span::new(decl.params.span.start + 1, decl.params.span.end - 1)
-                        .source_text(&source_text)
+                    args: get_args_span(decl.params.span)
+                        .source_text(source_text)
                         .to_owned(),
-                    body: body.span.source_text(&source_text),
+                    args_span: Some(get_args_span(decl.params.span)),
+                    body: body.span.source_text(source_text),
+                    body_span: body.span,
                     is_async: decl.r#async,
                 }));
             }
-            return None;
+            None
         }
-        Some(Declaration::VariableDeclaration(decl)) => {
+        Declaration::VariableDeclaration(decl) => {
             if decl.declarations.len() != 1 {
                 return None;
             }
@@ -336,23 +1297,18 @@ fn get_named_export_property<'a>(
             if let Some(d) = decl.declarations.first() {
                 if let BindingPatternKind::BindingIdentifier(_) = &d.id.kind {
                     if let Some(init) = &d.init {
-                        let key = rename_exports(d.id.get_identifier().map(|i| i.as_str()))
-                            .unwrap_or("$");
-
                         return match init {
                             Expression::FunctionExpression(func) => {
                                 if let Some(body) = &func.body {
                                     return Some(DefineRouteProperty::Method(Method {
                                         key,
                                         span: func.span,
-                                        // remove the parentheses
-                                        args: Span::new(
-                                            func.params.span.start + 1,
-                                            func.params.span.end - 1,
-                                        )
-                                        .source_text(&source_text)
-                                        .to_owned(),
-                                        body: body.span.source_text(&source_text),
+                                        args: get_args_span(func.params.span)
+                                            .source_text(source_text)
+                                            .to_owned(),
+                                        args_span: Some(get_args_span(func.params.span)),
+                                        body: body.span.source_text(source_text),
+                                        body_span: body.span,
                                         is_async: func.r#async,
                                     }));
                                 }
@@ -365,29 +1321,36 @@ fn get_named_export_property<'a>(
                                         StaticProperty {
                                             key,
                                             value: arrow_func.span.source_text(source_text),
+                                            span: arrow_func.span,
                                         },
                                     ));
                                 }
                                 return Some(DefineRouteProperty::Method(Method {
                                     key,
                                     span: arrow_func.span,
-                                    // remove the parentheses
-                                    args: Span::new(
-                                        arrow_func.params.span.start + 1,
-                                        arrow_func.params.span.end - 1,
-                                    )
-                                    .source_text(&source_text)
-                                    .to_owned(),
-                                    body: &arrow_func.body.span.source_text(&source_text),
+                                    args: get_args_span(arrow_func.params.span)
+                                        .source_text(source_text)
+                                        .to_owned(),
+                                    args_span: Some(get_args_span(arrow_func.params.span)),
+                                    body: arrow_func.body.span.source_text(source_text),
+                                    body_span: arrow_func.body.span,
                                     is_async: arrow_func.r#async,
                                 }));
                             }
+                            // `export const clientLoader = load`
+                            Expression::Identifier(ident) => {
+                                Some(DefineRouteProperty::StaticProperty(StaticProperty {
+                                    key,
+                                    value: ident.name.as_str(),
+                                    span: ident.span,
+                                }))
+                            }
                             _ => None,
                         };
                     }
                 }
             }
-            return None;
+            None
         }
         _ => None,
     }
@@ -403,15 +1366,16 @@ fn get_default_export_property<'a>(
                 return Some(DefineRouteProperty::Method(Method {
                     key: "$",
                     span: decl.span,
-                    // remove the parentheses
-                    args: Span::new(decl.params.span.start + 1, decl.params.span.end - 1)
-                        .source_text(&source_text)
+                    args: get_args_span(decl.params.span)
+                        .source_text(source_text)
                         .to_owned(),
-                    body: body.span.source_text(&source_text),
+                    args_span: Some(get_args_span(decl.params.span)),
+                    body: body.span.source_text(source_text),
+                    body_span: body.span,
                     is_async: decl.r#async,
                 }));
             }
-            return None;
+            None
         }
         ExportDefaultDeclarationKind::ArrowFunctionExpression(arrow_func) => {
             // Don't use shorthand for arrow functions with implicit returns, like `() => stuff`
@@ -419,65 +1383,55 @@ fn get_default_export_property<'a>(
                 return Some(DefineRouteProperty::StaticProperty(StaticProperty {
                     key: "$",
                     value: arrow_func.span.source_text(source_text),
+                    span: arrow_func.span,
                 }));
             }
-            return Some(DefineRouteProperty::Method(Method {
+            Some(DefineRouteProperty::Method(Method {
                 key: "$",
                 span: arrow_func.span,
-                // remove the parentheses
-                args: Span::new(
-                    arrow_func.params.span.start + 1,
-                    arrow_func.params.span.end - 1,
-                )
-                .source_text(&source_text)
-                .to_owned(),
-                body: &arrow_func.body.span.source_text(&source_text),
+                args: get_args_span(arrow_func.params.span)
+                    .source_text(source_text)
+                    .to_owned(),
+                args_span: Some(get_args_span(arrow_func.params.span)),
+                body: arrow_func.body.span.source_text(source_text),
+                body_span: arrow_func.body.span,
                 is_async: arrow_func.r#async,
-            }));
+            }))
         }
         _ => None,
     }
 }
 
-fn get_hook_declarators<'a>(
+/// Span of function params, without the parentheses
+pub fn get_args_span(params_span: Span) -> Span {
+    Span::new(params_span.start + 1, params_span.end - 1)
+}
+
+pub fn get_hook_declarators<'a>(
     function_body: &'a FunctionBody,
     source_text: &'a str,
+    hooks: &HookMatcher,
 ) -> Vec<(HookDeclarator<'a>, Span)> {
     function_body
         .statements
         .iter()
         .filter_map(|f| match f {
             Statement::VariableDeclaration(var_decl) => {
-                if let Some((whole_declaration, declarator_id)) =
-                    find_hook_usage(var_decl, "useLoaderData")
-                {
-                    return Some((
-                        HookDeclarator {
-                            name: "loaderData",
-                            source_text: declarator_id.source_text(source_text),
-                        },
-                        whole_declaration,
-                    ));
-                } else if let Some((whole_declaration, declarator_id)) =
-                    find_hook_usage(var_decl, "useActionData")
-                {
-                    return Some((
-                        HookDeclarator {
-                            name: "actionData",
-                            source_text: declarator_id.source_text(source_text),
-                        },
-                        whole_declaration,
-                    ));
-                } else {
-                    None
-                }
+                let (whole_declaration, declarator_id, name) = find_hook_usage(var_decl, hooks)?;
+                Some((
+                    HookDeclarator {
+                        name,
+                        source_text: declarator_id.source_text(source_text),
+                    },
+                    whole_declaration,
+                ))
             }
             _ => None,
         })
         .collect::<Vec<_>>()
 }
 
-fn rename_exports<'a>(old_name: Option<&'a str>) -> Option<&str> {
+pub fn rename_exports(old_name: Option<&str>) -> Option<&'static str> {
     match old_name {
         Some("loader") => Some("serverLoader"),
         Some("action") => Some("serverAction"),
@@ -485,7 +1439,10 @@ fn rename_exports<'a>(old_name: Option<&'a str>) -> Option<&str> {
     }
 }
 
-fn find_hook_usage(var_decl: &VariableDeclaration, hook_name: &str) -> Option<(Span, Span)> {
+fn find_hook_usage(
+    var_decl: &VariableDeclaration,
+    hooks: &HookMatcher,
+) -> Option<(Span, Span, &'static str)> {
     // Let's only care about single declarator, for now
     if var_decl.declarations.len() != 1 {
         return None;
@@ -495,8 +1452,8 @@ fn find_hook_usage(var_decl: &VariableDeclaration, hook_name: &str) -> Option<(S
 
     if let Some(Expression::CallExpression(call_expr)) = &declarator.init {
         if let Expression::Identifier(ident) = &call_expr.callee {
-            if ident.name == hook_name {
-                return Some((var_decl.span, declarator.id.span()));
+            if let Some(arg) = hooks.get_arg(&ident.name) {
+                return Some((var_decl.span, declarator.id.span(), arg));
             }
         }
     }
@@ -505,36 +1462,190 @@ fn find_hook_usage(var_decl: &VariableDeclaration, hook_name: &str) -> Option<(S
 }
 
 fn construct_component_params(hook_declarators: &Vec<HookDeclarator>) -> String {
+    let params = construct_component_param_list(hook_declarators);
+
+    if params.is_empty() {
+        return String::from("");
+    }
+
+    format!("{{ {} }}", params.join(", "))
+}
+
+fn construct_component_param_list(hook_declarators: &Vec<HookDeclarator>) -> Vec<String> {
     let mut params = vec![];
 
     for declarator in hook_declarators.iter() {
         let param = if declarator.name == declarator.source_text {
-            format!("{}", declarator.name)
+            declarator.name.to_string()
         } else {
             format!("{}: {}", declarator.name, declarator.source_text)
         };
         params.push(param.to_owned());
     }
 
-    if params.len() == 0 {
-        return String::from("");
+    params
+}
+
+/// The object literal passed to `export default defineRoute({ ... })`
+pub fn get_define_route_object<'a, 'b>(
+    default_export: &'b ExportDefaultDeclaration<'a>,
+    helpers: &[&str],
+) -> Option<&'b ObjectExpression<'a>> {
+    if let ExportDefaultDeclarationKind::CallExpression(call_expr) = &default_export.declaration {
+        if matches!(&call_expr.callee, Expression::Identifier(ident) if helpers.contains(&ident.name.as_str()))
+        {
+            if let Some(Expression::ObjectExpression(object)) =
+                call_expr.arguments.first().and_then(|a| a.as_expression())
+            {
+                return Some(object);
+            }
+        }
     }
 
-    format!("{{ {} }}", params.join(", "))
+    None
+}
+
+/// Params and body of the `Component` method or function in a defineRoute object
+fn get_define_route_component<'a, 'b>(
+    object: &'b ObjectExpression<'a>,
+) -> Option<(&'b FormalParameters<'a>, &'b FunctionBody<'a>)> {
+    object.properties.iter().find_map(|p| match p {
+        ObjectPropertyKind::ObjectProperty(p) if p.key.is_specific_static_name("Component") => {
+            match &p.value {
+                Expression::FunctionExpression(func) => {
+                    func.body.as_ref().map(|body| (&*func.params, &**body))
+                }
+                Expression::ArrowFunctionExpression(arrow_func) if !arrow_func.expression => {
+                    Some((&*arrow_func.params, &*arrow_func.body))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Params and body of a function declaration, or a variable declared as a function
+pub fn get_declaration_function<'a, 'b>(
+    declaration: &'b Declaration<'a>,
+) -> Option<(&'b FormalParameters<'a>, &'b FunctionBody<'a>)> {
+    match declaration {
+        Declaration::FunctionDeclaration(decl) => {
+            decl.body.as_ref().map(|body| (&*decl.params, &**body))
+        }
+        Declaration::VariableDeclaration(decl) if decl.declarations.len() == 1 => {
+            match &decl.declarations[0].init {
+                Some(Expression::FunctionExpression(func)) => {
+                    func.body.as_ref().map(|body| (&*func.params, &**body))
+                }
+                Some(Expression::ArrowFunctionExpression(arrow_func)) => {
+                    Some((&*arrow_func.params, &*arrow_func.body))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the component declared as `name` uses any data hooks
+fn has_hook_declarators(
+    program: &Program,
+    name: &str,
+    source_text: &str,
+    hooks: &HookMatcher,
+) -> bool {
+    find_declaration(program, name)
+        .and_then(|(_, declaration)| get_declaration_function(declaration))
+        .is_some_and(|(_, body)| !get_hook_declarators(body, source_text, hooks).is_empty())
+}
+
+/// Add hook declarators to the existing args of a defineRoute Component
+pub fn merge_component_params<'a>(
+    params: &FormalParameters,
+    hook_declarators: &Vec<HookDeclarator>,
+    source_text: &str,
+) -> Result<Option<Fix<'a>>, CodemodError> {
+    if hook_declarators.is_empty() {
+        return Ok(None);
+    }
+
+    let rule = |fix: Fix<'a>| {
+        fix.with_rule(
+            "merge-component-args",
+            "Add hook data to existing Component arguments",
+        )
+    };
+
+    if params.items.is_empty() && params.rest.is_none() {
+        let position = params.span.start + 1;
+        return Ok(Some(rule(Fix::insert(
+            construct_component_params(hook_declarators),
+            Span::new(position, position),
+        ))));
+    }
+
+    let pattern = match params.items.first().map(|p| &p.pattern.kind) {
+        Some(BindingPatternKind::ObjectPattern(pattern)) if params.items.len() == 1 => pattern,
+        _ => {
+            return Err(CodemodError::UnsupportedComponentArgs(
+                get_args_span(params.span)
+                    .source_text(source_text)
+                    .to_owned(),
+            ))
+        }
+    };
+
+    let conflicts = hook_declarators
+        .iter()
+        .filter(|hook| {
+            pattern
+                .properties
+                .iter()
+                .any(|p| p.key.is_specific_static_name(hook.name))
+        })
+        .map(|hook| hook.name.to_owned())
+        .collect::<Vec<_>>();
+    if !conflicts.is_empty() {
+        return Err(CodemodError::PropertyConflicts(conflicts));
+    }
+
+    let new_params = construct_component_param_list(hook_declarators).join(", ");
+    let fix = if let Some(last) = pattern.properties.last() {
+        Fix::insert(
+            format!(", {}", new_params),
+            Span::new(last.span.end, last.span.end),
+        )
+    } else if let Some(rest) = &pattern.rest {
+        Fix::insert(
+            format!("{}, ", new_params),
+            Span::new(rest.span.start, rest.span.start),
+        )
+    } else {
+        Fix::insert(
+            format!(" {} ", new_params),
+            Span::new(pattern.span.start + 1, pattern.span.start + 1),
+        )
+    };
+
+    Ok(Some(rule(fix)))
 }
 
 #[cfg(test)]
 mod tests {
     use oxc_span::SourceType;
 
-    use std::cmp;
-
     use super::codemod;
+    use crate::{
+        codemod_models::{CodemodError, CodemodOptions, ResourceRouteForm},
+        test_utils::outdent,
+    };
 
     #[test]
     fn test_empty() {
         let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
-        assert_eq!(codemod(&"".to_owned(), source_type).unwrap(), "");
+        let options = CodemodOptions::default();
+        assert_eq!(codemod("", source_type, &options).unwrap().code, "");
     }
 
     #[test]
@@ -648,21 +1759,286 @@ mod tests {
     }
 
     #[test]
-    fn test_loader_function_named() {
+    fn test_component_identifier() {
         let input = r#"
+            import { useLoaderData } from "@remix-run/react";
+
             export function loader() {
               return { hello: "world" };
             }
+
+            const UsersPage = () => {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.hello}</h1>;
+            };
+
+            export default UsersPage;
         "#;
-        assert_snapshot("loader_function_named", input);
+        assert_snapshot("component_identifier", input);
     }
 
     #[test]
-    fn test_loader_function_named_args() {
+    fn test_component_wrapped() {
         let input = r#"
-            import type { LoaderFunctionArgs } from "@remix-run/node";
+            import { memo } from "react";
+            import { useLoaderData } from "@remix-run/react";
 
-            export function loader({ params, context, request, response }: LoaderFunctionArgs) {
+            export function loader() {
+              return { hello: "world" };
+            }
+
+            function UsersPage() {
+              const data = useLoaderData();
+              return <h1>{data.hello}</h1>;
+            }
+
+            export default memo(UsersPage);
+        "#;
+        let output = codemod(
+            &outdent(input),
+            SourceType::from_path("path/to/file.tsx").unwrap(),
+            &CodemodOptions::default(),
+        )
+        .unwrap();
+        assert_snapshot("component_wrapped", input);
+        assert_eq!(
+            output
+                .diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec!["13:16 Component is wrapped in `memo()`, data hooks inside it weren't moved to component args (move-default-export)"]
+        );
+    }
+
+    #[test]
+    fn test_root_layout() {
+        let input = r#"
+            import { Links, Meta, Outlet, Scripts, useLoaderData } from "@remix-run/react";
+
+            export function loader() {
+              return { lang: "en" };
+            }
+
+            export function Layout({ children }: { children: React.ReactNode }) {
+              const data = useLoaderData<typeof loader>();
+              return (
+                <html lang={data?.lang}>
+                  <head>
+                    <Meta />
+                    <Links />
+                  </head>
+                  <body>
+                    {children}
+                    <Scripts />
+                  </body>
+                </html>
+              );
+            }
+
+            export default function App() {
+              return <Outlet />;
+            }
+        "#;
+        let input = outdent(input);
+        let options = CodemodOptions {
+            is_root_route: true,
+            ..Default::default()
+        };
+        let output = codemod(
+            &input,
+            SourceType::from_path("path/to/root.tsx").unwrap(),
+            &options,
+        )
+        .unwrap();
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+            description => &input,
+        }, {
+            insta::assert_snapshot!("root_layout", output.code);
+        });
+        assert_eq!(
+            output
+                .diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec!["7:17 `Layout` also renders around `ErrorBoundary`, where `loaderData` is undefined (root-layout)"]
+        );
+    }
+
+    #[test]
+    fn test_layout_outside_root() {
+        let input = r#"
+            export function Layout({ children }: { children: React.ReactNode }) {
+              return <main>{children}</main>;
+            }
+
+            export default function Users() {
+              return null;
+            }
+        "#;
+        let output = codemod(
+            &outdent(input),
+            SourceType::from_path("path/to/file.tsx").unwrap(),
+            &CodemodOptions::default(),
+        )
+        .unwrap();
+        assert_snapshot("layout_outside_root", input);
+        assert_eq!(
+            output
+                .diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec!["1:1 `Layout` is only used in the root route, it was left as an export (root-layout)"]
+        );
+    }
+
+    #[test]
+    fn test_resource_route_api() {
+        let input = r#"
+            import { json } from "@remix-run/node";
+            import type { ActionFunctionArgs, LoaderFunctionArgs } from "@remix-run/node";
+
+            export async function loader({ params }: LoaderFunctionArgs) {
+              const user = await db.user.find(params.userId);
+              if (!user) {
+                throw json({ error: "Not found" }, { status: 404 });
+              }
+              return json(user, { headers: { "Cache-Control": "max-age=60" } });
+            }
+
+            export const action = async ({ request }: ActionFunctionArgs) => {
+              const body = await request.json();
+              return json(await db.user.update(body), 201);
+            };
+        "#;
+        assert_resource_route_snapshot("resource_route_api", input);
+    }
+
+    #[test]
+    fn test_resource_route_rss() {
+        let input = r#"
+            export async function loader() {
+              const posts = await getPosts();
+              const rss = renderFeed(posts);
+              return new Response(rss, {
+                headers: { "Content-Type": "application/rss+xml; charset=utf-8" },
+              });
+            }
+        "#;
+        assert_resource_route_snapshot("resource_route_rss", input);
+    }
+
+    #[test]
+    fn test_resource_route_image_proxy() {
+        let input = r#"
+            import type { LoaderFunctionArgs } from "@remix-run/node";
+
+            export const loader = async ({ request }: LoaderFunctionArgs) => {
+              const url = new URL(request.url).searchParams.get("src");
+              const image = await fetch(url);
+              return new Response(image.body, {
+                headers: {
+                  "Content-Type": image.headers.get("Content-Type"),
+                  "Cache-Control": "public, max-age=31536000, immutable",
+                },
+              });
+            };
+        "#;
+        assert_resource_route_snapshot("resource_route_image_proxy", input);
+    }
+
+    #[test]
+    fn test_resource_route_flag() {
+        let input = outdent(
+            r#"
+            export async function loader() {
+              return new Response("ok");
+            }
+        "#,
+        );
+        let options = CodemodOptions {
+            resource_route: Some(ResourceRouteForm::Flag),
+            ..Default::default()
+        };
+        let source_type = SourceType::from_path("path/to/healthcheck.ts").unwrap();
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+            description => &input,
+        }, {
+            insta::assert_snapshot!("resource_route_flag", codemod(&input, source_type, &options).unwrap().code);
+        });
+    }
+
+    #[test]
+    fn test_javascript_route() {
+        let input = r#"
+            import { json } from "@remix-run/node";
+            import { Form, useActionData, useLoaderData } from "@remix-run/react";
+
+            export async function loader({ params }) {
+              return json({ user: await getUser(params.userId) });
+            }
+
+            export const action = async ({ request }) => {
+              const formData = await request.formData();
+              return json({ ok: formData.has("name") });
+            };
+
+            export const meta = ({ data }) => [{ title: data.user.name }];
+
+            export default function User() {
+              const { user } = useLoaderData();
+              const actionData = useActionData();
+              return (
+                <Form method="post">
+                  <input name="name" defaultValue={user.name} />
+                  {actionData?.ok ? "Saved" : null}
+                </Form>
+              );
+            }
+        "#;
+        assert_snapshot_with_path("javascript_route", input, "path/to/users.$userId.js");
+    }
+
+    #[test]
+    fn test_jsx_route() {
+        let input = r#"
+            import { isRouteErrorResponse, useLoaderData, useRouteError } from "@remix-run/react";
+
+            export const loader = () => ({ posts: [] });
+
+            export default () => {
+              const data = useLoaderData();
+              return <ul>{data.posts.map((post) => <li key={post.id}>{post.title}</li>)}</ul>;
+            };
+
+            export function ErrorBoundary() {
+              const error = useRouteError();
+              return <p>{isRouteErrorResponse(error) ? error.statusText : "Oops"}</p>;
+            }
+        "#;
+        assert_snapshot_with_path("jsx_route", input, "path/to/posts.jsx");
+    }
+
+    #[test]
+    fn test_loader_function_named() {
+        let input = r#"
+            export function loader() {
+              return { hello: "world" };
+            }
+        "#;
+        assert_snapshot("loader_function_named", input);
+    }
+
+    #[test]
+    fn test_loader_function_named_args() {
+        let input = r#"
+            import type { LoaderFunctionArgs } from "@remix-run/node";
+
+            export function loader({ params, context, request, response }: LoaderFunctionArgs) {
               return { hello: "world" };
             }
         "#;
@@ -876,6 +2252,115 @@ mod tests {
         assert_snapshot("component_client_loader_hydrate", input);
     }
 
+    #[test]
+    fn test_client_loader_hydrate_as_const() {
+        let input = r#"
+            export async function clientLoader({ serverLoader }) {
+              return await serverLoader();
+            }
+            clientLoader.hydrate = true as const;
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_snapshot("client_loader_hydrate_as_const", input);
+    }
+
+    #[test]
+    fn test_client_loader_hydrate_object_assign() {
+        let input = r#"
+            export async function clientLoader({ serverLoader }) {
+              return await serverLoader();
+            }
+            Object.assign(clientLoader, { hydrate: true });
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_snapshot("client_loader_hydrate_object_assign", input);
+    }
+
+    #[test]
+    fn test_client_loader_hydrate_export_assign() {
+        let input = r#"
+            import type { ClientLoaderFunctionArgs } from "@remix-run/react";
+
+            export const clientLoader = Object.assign(
+              async ({ serverLoader }: ClientLoaderFunctionArgs) => {
+                return await serverLoader();
+              },
+              { hydrate: true as const },
+            );
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_snapshot("client_loader_hydrate_export_assign", input);
+    }
+
+    #[test]
+    fn test_client_loader_hydrate_export_assign_identifier() {
+        let input = r#"
+            async function load({ serverLoader }) {
+              return await serverLoader();
+            }
+
+            export const clientLoader = Object.assign(load, { hydrate: true });
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_snapshot("client_loader_hydrate_export_assign_identifier", input);
+    }
+
+    #[test]
+    fn test_hydrate_fallback_pairing() {
+        let without_fallback = r#"
+            export async function clientLoader({ serverLoader }) {
+              return await serverLoader();
+            }
+            clientLoader.hydrate = true;
+
+            export default function Component() {
+              return null;
+            }
+        "#;
+        assert_eq!(
+            get_diagnostics(without_fallback),
+            vec!["4:24 `clientLoader.hydrate` is set without a `HydrateFallback` to render until the client loader finishes (hydrate-fallback)"]
+        );
+
+        let without_hydrate = r#"
+            export function loader() {
+              return { ok: true };
+            }
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_eq!(
+            get_diagnostics(without_hydrate),
+            vec!["5:8 `HydrateFallback` is never rendered, there's no `clientLoader` that runs on hydration (hydrate-fallback)"]
+        );
+
+        // Client loaders without a server loader always run on hydration
+        let client_only = r#"
+            export async function clientLoader() {
+              return { ok: true };
+            }
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert!(get_diagnostics(client_only).is_empty());
+    }
+
     #[test]
     fn test_multiple_components() {
         let input = r#"
@@ -895,9 +2380,24 @@ mod tests {
               const data = useLoaderData<typeof loader>();
             }
         "#;
+        // `loader` is still referenced by the other components, so it stays
         assert_snapshot("multiple_components", input);
     }
 
+    #[test]
+    fn test_type_reference_keeps_local_binding() {
+        let input = r#"
+            import type { SerializeFrom } from "@remix-run/node";
+
+            export function loader() {
+              return { hello: "world" };
+            }
+
+            type LoaderData = SerializeFrom<typeof loader>;
+        "#;
+        assert_snapshot("type_reference_keeps_local_binding", input);
+    }
+
     #[test]
     fn test_unrelated_function_args() {
         let input = r#"
@@ -926,44 +2426,228 @@ mod tests {
         assert_snapshot("mix_loader_unrelated_function_args", input);
     }
 
+    #[test]
+    fn test_merge_into_define_route() {
+        let input = r#"
+            import { useLoaderData } from "@remix-run/react";
+
+            export const meta = () => [{ title: "Users" }];
+
+            export function action({ request }: ActionFunctionArgs) {
+              return { ok: true };
+            }
+
+            export default defineRoute({
+              serverLoader() {
+                return { users: [] };
+              },
+              Component({ loaderData }) {
+                return <h1>{loaderData.users.length}</h1>;
+              },
+            });
+        "#;
+        assert_snapshot("merge_into_define_route", input);
+    }
+
+    #[test]
+    fn test_merge_into_define_route_without_trailing_comma() {
+        let input = r#"
+            export const links = () => [{ rel: "stylesheet", href: "/users.css" }];
+
+            export default defineRoute({
+              Component() {
+                return <h1>Users</h1>;
+              }
+            });
+        "#;
+        assert_snapshot("merge_into_define_route_without_trailing_comma", input);
+    }
+
+    #[test]
+    fn test_merge_hooks_into_define_route_component() {
+        let input = r#"
+            import { useActionData, useLoaderData } from "@remix-run/react";
+
+            export default defineRoute({
+              serverLoader() {
+                return { users: [] };
+              },
+              Component({ loaderData: { users } }) {
+                const actionData = useActionData<typeof action>();
+                return <h1>{users.length} {actionData?.ok}</h1>;
+              },
+              ErrorBoundary() {
+                const data = useLoaderData<typeof loader>();
+                return <h1>Error</h1>;
+              },
+            });
+        "#;
+        assert_snapshot("merge_hooks_into_define_route_component", input);
+    }
+
+    #[test]
+    fn test_merge_hooks_into_define_route_component_without_args() {
+        let input = r#"
+            export default defineRoute({
+              Component: () => {
+                const data = useLoaderData<typeof loader>();
+                return <h1>{data.users.length}</h1>;
+              },
+            });
+        "#;
+        assert_snapshot(
+            "merge_hooks_into_define_route_component_without_args",
+            input,
+        );
+    }
+
+    #[test]
+    fn test_merge_into_define_route_conflict() {
+        let input = outdent(
+            r#"
+            export const meta = () => [{ title: "Users" }];
+
+            export default defineRoute({
+              meta: () => [{ title: "Old" }],
+            });
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions::default();
+        match codemod(&input, source_type, &options) {
+            Err(CodemodError::PropertyConflicts(keys)) => assert_eq!(keys, vec!["meta"]),
+            result => panic!("expected property conflicts, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_crlf_component_loader() {
+        let input = r#"
+            import { useLoaderData } from "@remix-run/react";
+
+            export function loader() {
+              return { hello: "world" };
+            }
+
+            export default function() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.hello}</h1>;
+            }
+        "#;
+        assert_snapshot_crlf("crlf_component_loader", input, false);
+    }
+
+    #[test]
+    fn test_crlf_client_loader_hydrate() {
+        let input = r#"
+            import type { ClientLoaderFunctionArgs } from "@remix-run/node";
+
+            export async function clientLoader({ serverLoader }: ClientLoaderFunctionArgs) {
+              return await serverLoader();
+            }
+            clientLoader.hydrate = true;
+
+            export function HydrateFallback() {
+              return <p>Loading...</p>;
+            }
+        "#;
+        assert_snapshot_crlf("crlf_client_loader_hydrate", input, false);
+    }
+
+    #[test]
+    fn test_crlf_bom() {
+        let input = r#"
+            export const meta = () => [{ title: "Hello" }];
+
+            export default function Route() {
+              return <div>hello</div>;
+            }
+        "#;
+        assert_snapshot_crlf("crlf_bom", input, true);
+    }
+
+    #[test]
+    fn test_bom_without_changes() {
+        let input = "\u{feff}export function unrelated() {}\n".to_owned();
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions::default();
+        assert_eq!(codemod(&input, source_type, &options).unwrap().code, input);
+    }
+
+    #[test]
+    fn test_mixed_line_endings_without_changes() {
+        let input = "const a = 1;\nconst b = `x\r\ny`;\r\nexport const c = 2;\r\n";
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let output = codemod(input, source_type, &CodemodOptions::default()).unwrap();
+        assert_eq!(output.code, input);
+        assert!(!output.changed);
+    }
+
     fn assert_snapshot(name: &str, input: &str) {
+        assert_snapshot_with_path(name, input, "path/to/file.tsx");
+    }
+
+    fn assert_resource_route_snapshot(name: &str, input: &str) {
         let input = outdent(input);
         let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions {
+            resource_route: Some(ResourceRouteForm::default()),
+            ..Default::default()
+        };
         insta::with_settings!({
             prepend_module_to_snapshot => false,
             description => &input,
         }, {
-            insta::assert_snapshot!(name, codemod(&input, source_type).unwrap());
+            insta::assert_snapshot!(name, codemod(&input, source_type, &options).unwrap().code);
         })
     }
 
-    /// Remove leading whitespace from each line, preserving relative indentation.
-    /// Remove the first and the last lines.
-    fn outdent(input: &str) -> String {
-        let length = input.len();
-        let mut output = String::with_capacity(length);
-
-        let mut base_indent = 0;
-
-        let input_body = skip_last(input.lines().skip(1));
-
-        for (i, line) in input_body.enumerate() {
-            if i == 0 {
-                base_indent = line.chars().take_while(|c| c.is_whitespace()).count();
-            }
+    fn get_diagnostics(input: &str) -> Vec<String> {
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        codemod(&outdent(input), source_type, &CodemodOptions::default())
+            .unwrap()
+            .diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect()
+    }
 
-            let indent = line.chars().take_while(|c| c.is_whitespace()).count();
-            let indent = cmp::min(base_indent, indent);
+    fn assert_snapshot_with_path(name: &str, input: &str, path: &str) {
+        let input = outdent(input);
+        let source_type = SourceType::from_path(path).unwrap();
+        let options = CodemodOptions::default();
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+            description => &input,
+        }, {
+            insta::assert_snapshot!(name, codemod(&input, source_type, &options).unwrap().code);
+        })
+    }
 
-            output.push_str(&line[indent..]);
-            output.push('\n');
-        }
+    /// Same as `assert_snapshot`, but with CRLF line endings and an optional BOM.
+    /// Carriage returns are escaped in the snapshot, as insta normalizes them.
+    fn assert_snapshot_crlf(name: &str, input: &str, bom: bool) {
+        let input = outdent(input).replace('\n', "\r\n");
+        let input = if bom {
+            format!("\u{feff}{}", input)
+        } else {
+            input
+        };
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions::default();
+        let output = codemod(&input, source_type, &options).unwrap().code;
 
-        output
-    }
+        assert_eq!(output.starts_with('\u{feff}'), bom);
+        assert!(
+            !output.replace("\r\n", "").contains(['\r', '\n']),
+            "mixed line endings in output"
+        );
 
-    fn skip_last<T>(mut iter: impl Iterator<Item = T>) -> impl Iterator<Item = T> {
-        let last = iter.next();
-        iter.scan(last, |state, item| std::mem::replace(state, Some(item)))
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+            description => &input,
+        }, {
+            insta::assert_snapshot!(name, output.replace('\r', "\\r").replace('\u{feff}', "\\u{feff}"));
+        })
     }
 }
@@ -1,5 +1,12 @@
 use oxc_span::Span;
 
+use std::fmt;
+
+use crate::{
+    data_hooks::DataHooks, definition_helpers::DefinitionHelpers, fixer::DroppedFix,
+    sourcemap::ByteMap, utils::get_line_and_column,
+};
+
 #[derive(Debug, Clone)]
 pub struct HookDeclarator<'a> {
     pub name: &'a str,
@@ -13,19 +20,36 @@ pub enum DefineRouteProperty<'a> {
 }
 
 impl<'a> DefineRouteProperty<'a> {
+    pub fn key(&self) -> &'a str {
+        match self {
+            DefineRouteProperty::StaticProperty(p) => p.key,
+            DefineRouteProperty::Method(p) => p.key,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            DefineRouteProperty::StaticProperty(p) => p.span,
+            DefineRouteProperty::Method(p) => p.span,
+        }
+    }
+
     pub fn default_name(&self, new_key: &'a str) -> Self {
         match self {
             DefineRouteProperty::StaticProperty(p) => {
                 DefineRouteProperty::StaticProperty(StaticProperty {
                     key: if p.key == "$" { new_key } else { p.key },
                     value: p.value,
+                    span: p.span,
                 })
             }
             DefineRouteProperty::Method(p) => DefineRouteProperty::Method(Method {
                 key: if p.key == "$" { new_key } else { p.key },
                 span: p.span,
                 args: p.args.clone(),
+                args_span: p.args_span,
                 body: p.body,
+                body_span: p.body_span,
                 is_async: p.is_async,
             }),
         }
@@ -37,7 +61,9 @@ impl<'a> DefineRouteProperty<'a> {
                 key: p.key,
                 span: p.span,
                 args: new_args,
+                args_span: None,
                 body: p.body,
+                body_span: p.body_span,
                 is_async: p.is_async,
             }),
             _ => self.clone(),
@@ -49,6 +75,8 @@ impl<'a> DefineRouteProperty<'a> {
 pub struct StaticProperty<'a> {
     pub key: &'a str,
     pub value: &'a str,
+    /// Span of the value in the source text
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +84,218 @@ pub struct Method<'a> {
     pub key: &'a str,
     pub span: Span,
     pub args: String,
+    /// Span of the args in the source text, if they weren't rewritten
+    pub args_span: Option<Span>,
     pub body: &'a str,
+    pub body_span: Span,
     pub is_async: bool,
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct CodemodOptions {
+    /// Produce a map from the transformed code back to the original source
+    pub source_map: bool,
+    /// File name of the route without its extension, for `./+types/<name>` imports
+    pub route_name: Option<String>,
+    /// Import from react-router instead of the `@remix-run/*` packages
+    pub rewrite_imports: bool,
+    /// Hooks whose data becomes component args, and helpers to unwrap in loaders
+    pub data_hooks: DataHooks,
+    /// The root route, which can also export `Layout`
+    pub is_root_route: bool,
+    /// How route modules without a component are written, `None` writes
+    /// them with defineRoute like any other route
+    pub resource_route: Option<ResourceRouteForm>,
+    /// Pass-through helpers around route exports, unwrapped when they move
+    pub definition_helpers: DefinitionHelpers,
+    /// File of the route relative to the app directory, like `routes/users.tsx`
+    pub route_file: Option<String>,
+    /// All routes of the routes manifest, to locate parent routes by id
+    pub routes: Vec<ManifestRoute>,
+    /// Rewrite URLs of links, forms, navigations and redirects to `href()`,
+    /// only together with `rewrite_imports` as `href()` comes from react-router
+    pub typed_links: bool,
+}
+
+/// Route of the routes manifest
+#[derive(Debug, Clone)]
+pub struct ManifestRoute {
+    pub id: String,
+    /// Relative to the app directory
+    pub file: String,
+    /// URL pattern like `/users/:userId`, `None` for layout routes without a path
+    pub path: Option<String>,
+}
+
+impl CodemodOptions {
+    /// Functions whose object argument is a route module, see `get_define_route_object`
+    pub fn define_route_helpers(&self) -> Vec<&str> {
+        match &self.resource_route {
+            Some(ResourceRouteForm::Helper(name)) => vec!["defineRoute", name],
+            _ => vec!["defineRoute"],
+        }
+    }
+}
+
+/// Default export of resource routes, modules with no component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceRouteForm {
+    /// `export default defineResourceRoute({ ... })`, with the given name
+    Helper(String),
+    /// `export default defineRoute({ resource: true, ... })`
+    Flag,
+}
+
+impl Default for ResourceRouteForm {
+    fn default() -> Self {
+        ResourceRouteForm::Helper("defineResourceRoute".to_owned())
+    }
+}
+
+impl ResourceRouteForm {
+    /// `flag`, or the name of the helper
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value == "flag" {
+            return Ok(ResourceRouteForm::Flag);
+        }
+        let is_identifier = value
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+        if is_identifier {
+            Ok(ResourceRouteForm::Helper(value.to_owned()))
+        } else {
+            Err(format!(
+                "Invalid resource route form `{}`, expected `flag` or a function name",
+                value
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CodemodOutput {
+    pub code: String,
+    pub source_map: Option<ByteMap>,
+    /// Things that were left as they were and need a look
+    pub diagnostics: Vec<Diagnostic>,
+    /// Whether the fixes changed anything, `code` is the input otherwise
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule_id: &'static str,
+    pub message: String,
+    /// Position in the text the transform was given
+    pub span: Span,
+    /// One-based position in the original file, see `locate`
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Diagnostic {
+    pub fn new(rule_id: &'static str, message: String, span: Span) -> Self {
+        Self {
+            rule_id,
+            message,
+            span,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    /// Fill in the line and column of the span start
+    pub fn locate(self, source_text: &str) -> Self {
+        let (line, column) = get_line_and_column(source_text, self.span.start);
+        Self {
+            line,
+            column,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {} ({})",
+            self.line, self.column, self.message, self.rule_id
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum CodemodError {
+    AlreadyMigrated,
+    /// Some fixes overlapped or were invalid, applying the rest would lose code
+    DroppedFixes(Vec<DroppedFix>),
+    /// Properties that already exist in the defineRoute object or Component args
+    PropertyConflicts(Vec<String>),
+    /// Component args we don't know how to add hook data to
+    UnsupportedComponentArgs(String),
+    /// defineRoute properties that can't be turned back into exports
+    UnsupportedRouteProperty(String),
+    /// The transformed code doesn't parse
+    InvalidOutput(Vec<String>),
+    /// The transformed code references identifiers that no longer exist
+    BrokenReferences(Vec<BrokenReference>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenReference {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for CodemodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodemodError::AlreadyMigrated => {
+                write!(f, "File already has a new module default export")
+            }
+            CodemodError::DroppedFixes(dropped) => {
+                write!(f, "Conflicting fixes, refusing to write a partial result:")?;
+                for d in dropped.iter() {
+                    write!(f, "\n  - {}", d)?;
+                }
+                Ok(())
+            }
+            CodemodError::PropertyConflicts(keys) => {
+                let keys = keys
+                    .iter()
+                    .map(|key| format!("`{}`", key))
+                    .collect::<Vec<_>>();
+                write!(f, "Properties already defined: {}", keys.join(", "))
+            }
+            CodemodError::UnsupportedComponentArgs(args) => {
+                write!(f, "Can't add hook data to Component args `({})`", args)
+            }
+            CodemodError::UnsupportedRouteProperty(property) => {
+                write!(f, "Can't turn `{}` into a route export", property)
+            }
+            CodemodError::InvalidOutput(errors) => {
+                write!(f, "Transformed code doesn't parse:")?;
+                for error in errors.iter() {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+            CodemodError::BrokenReferences(broken) => {
+                write!(
+                    f,
+                    "Transformed code references identifiers that no longer exist:"
+                )?;
+                for b in broken.iter() {
+                    write!(f, "\n  - `{}` at {}:{}", b.name, b.line, b.column)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
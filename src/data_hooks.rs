@@ -0,0 +1,285 @@
+//! Which hooks provide route data to components, and which helpers wrap the
+//! data returned from loaders. Defaults to the Remix ones, with presets for
+//! libraries that add their own serialization on top.
+
+use oxc_ast::ast::{ImportDeclarationSpecifier, Program};
+
+use crate::imports::get_import_declarations;
+
+/// `const data = useLoaderData()` becomes the `loaderData` component argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookMapping {
+    pub hook: String,
+    pub module: String,
+    /// `loaderData` or `actionData`
+    pub arg: &'static str,
+}
+
+/// `return json(data)` becomes `return data`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseHelperMapping {
+    pub helper: String,
+    pub module: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DataHooks {
+    pub hooks: Vec<HookMapping>,
+    pub response_helpers: Vec<ResponseHelperMapping>,
+}
+
+/// Modules the Remix hooks are imported from
+const HOOK_MODULES: [&str; 2] = ["@remix-run/react", "react-router"];
+
+/// Modules the Remix response helpers are imported from, which also export `data()`
+pub const RUNTIME_MODULES: [&str; 6] = [
+    "@remix-run/node",
+    "@remix-run/cloudflare",
+    "@remix-run/deno",
+    "@remix-run/server-runtime",
+    "@remix-run/react",
+    "react-router",
+];
+
+pub const PRESETS: [&str; 2] = ["remix-typedjson", "remix-superjson"];
+
+impl Default for DataHooks {
+    fn default() -> Self {
+        let mut data_hooks = Self {
+            hooks: vec![],
+            response_helpers: vec![],
+        };
+        for module in HOOK_MODULES {
+            data_hooks.add_hook("useLoaderData", module, "loaderData");
+            data_hooks.add_hook("useActionData", module, "actionData");
+        }
+        for module in RUNTIME_MODULES {
+            data_hooks.add_response_helper("json", module);
+            data_hooks.add_response_helper("defer", module);
+        }
+        data_hooks.add_response_helper("typedjson", "remix-typedjson");
+        data_hooks.add_response_helper("typeddefer", "remix-typedjson");
+        data_hooks
+    }
+}
+
+impl DataHooks {
+    pub fn add_hook(&mut self, hook: &str, module: &str, arg: &'static str) {
+        let mapping = HookMapping {
+            hook: hook.to_owned(),
+            module: module.to_owned(),
+            arg,
+        };
+        if !self.hooks.contains(&mapping) {
+            self.hooks.push(mapping);
+        }
+    }
+
+    pub fn add_response_helper(&mut self, helper: &str, module: &str) {
+        let mapping = ResponseHelperMapping {
+            helper: helper.to_owned(),
+            module: module.to_owned(),
+        };
+        if !self.response_helpers.contains(&mapping) {
+            self.response_helpers.push(mapping);
+        }
+    }
+
+    /// Add the hooks and helpers of a library, see `PRESETS`
+    pub fn add_preset(&mut self, preset: &str) -> Result<(), String> {
+        match preset {
+            "remix-typedjson" => {
+                self.add_hook("useTypedLoaderData", preset, "loaderData");
+                self.add_hook("useTypedActionData", preset, "actionData");
+                self.add_response_helper("typedjson", preset);
+                self.add_response_helper("typeddefer", preset);
+            }
+            "remix-superjson" => {
+                self.add_hook("useSuperLoaderData", preset, "loaderData");
+                self.add_hook("useSuperActionData", preset, "actionData");
+                self.add_response_helper("json", preset);
+            }
+            _ => {
+                return Err(format!(
+                    "Unknown preset `{}`, expected one of: {}",
+                    preset,
+                    PRESETS.join(", ")
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a `<hook>:<module>=<loaderData|actionData>` mapping
+    pub fn add_hook_from_str(&mut self, value: &str) -> Result<(), String> {
+        let error = || {
+            format!(
+                "Invalid hook mapping `{}`, expected `<hook>:<module>=<loaderData|actionData>`",
+                value
+            )
+        };
+        let (hook, rest) = value.split_once(':').ok_or_else(error)?;
+        let (module, arg) = rest.rsplit_once('=').ok_or_else(error)?;
+        let arg = match arg {
+            "loaderData" => "loaderData",
+            "actionData" => "actionData",
+            _ => return Err(error()),
+        };
+        if hook.is_empty() || module.is_empty() {
+            return Err(error());
+        }
+        self.add_hook(hook, module, arg);
+        Ok(())
+    }
+}
+
+/// Hook mappings resolved against the imports of one file
+pub struct HookMatcher<'a> {
+    /// Local name of each imported hook, and its component argument
+    imported: Vec<(&'a str, &'static str)>,
+    /// Imported names that aren't hooks, even if their name matches one
+    other_imports: Vec<&'a str>,
+    /// Hooks that can also be used without an import, e.g. auto-imports
+    globals: Vec<(&'a str, &'static str)>,
+}
+
+impl<'a> HookMatcher<'a> {
+    pub fn new(program: &Program<'a>, data_hooks: &'a DataHooks) -> Self {
+        let mut imported = vec![];
+        let mut other_imports = vec![];
+        for decl in get_import_declarations(program) {
+            for specifier in decl.specifiers.iter().flatten() {
+                let ImportDeclarationSpecifier::ImportSpecifier(specifier) = specifier else {
+                    continue;
+                };
+                let mapping = data_hooks.hooks.iter().find(|mapping| {
+                    mapping.module == decl.source.value.as_str()
+                        && mapping.hook == specifier.imported.name().as_str()
+                });
+                match mapping {
+                    Some(mapping) => imported.push((specifier.local.name.as_str(), mapping.arg)),
+                    None => other_imports.push(specifier.local.name.as_str()),
+                }
+            }
+        }
+
+        let globals = data_hooks
+            .hooks
+            .iter()
+            .map(|mapping| (mapping.hook.as_str(), mapping.arg))
+            .collect();
+
+        Self {
+            imported,
+            other_imports,
+            globals,
+        }
+    }
+
+    /// Component argument the result of calling `callee` goes to
+    pub fn get_arg(&self, callee: &str) -> Option<&'static str> {
+        if let Some((_, arg)) = self.imported.iter().find(|(local, _)| *local == callee) {
+            return Some(arg);
+        }
+        if self.other_imports.contains(&callee) {
+            return None;
+        }
+        self.globals
+            .iter()
+            .find(|(hook, _)| *hook == callee)
+            .map(|(_, arg)| *arg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::SourceType;
+
+    use super::DataHooks;
+    use crate::{
+        codemod::codemod,
+        codemod_models::CodemodOptions,
+        test_utils::{assert_codemod_snapshot, outdent},
+    };
+
+    #[test]
+    fn test_remix_typedjson_preset() {
+        let input = r#"
+            import { typedjson, useTypedActionData, useTypedLoaderData } from "remix-typedjson";
+
+            export async function loader() {
+              return typedjson({ createdAt: new Date() });
+            }
+
+            export async function action() {
+              return typedjson({ ok: true });
+            }
+
+            export default function Component() {
+              const { createdAt } = useTypedLoaderData<typeof loader>();
+              const actionData = useTypedActionData<typeof action>();
+              return <p>{createdAt.toISOString()} {actionData?.ok}</p>;
+            }
+        "#;
+        assert_snapshot("remix_typedjson_preset", "remix-typedjson", input);
+    }
+
+    #[test]
+    fn test_remix_superjson_preset() {
+        let input = r#"
+            import { json, useSuperLoaderData } from "remix-superjson";
+
+            export const loader = async () => json({ createdAt: new Date() });
+
+            export default function Component() {
+              const data = useSuperLoaderData<typeof loader>();
+              return <p>{data.createdAt.toISOString()}</p>;
+            }
+        "#;
+        assert_snapshot("remix_superjson_preset", "remix-superjson", input);
+    }
+
+    #[test]
+    fn test_custom_hook_mapping() {
+        let mut data_hooks = DataHooks::default();
+        data_hooks
+            .add_hook_from_str("useRouteData:~/utils/data=loaderData")
+            .unwrap();
+        assert_eq!(data_hooks.hooks.last().unwrap().module, "~/utils/data");
+        assert!(data_hooks
+            .add_hook_from_str("useRouteData=loaderData")
+            .is_err());
+        assert!(data_hooks
+            .add_hook_from_str("useRouteData:~/utils/data=routeData")
+            .is_err());
+    }
+
+    #[test]
+    fn test_hook_from_other_module() {
+        // Same name as the preset hook, but a different library
+        let input = r#"
+            import { useTypedLoaderData } from "~/utils/data";
+
+            export async function loader() {
+              return { hello: "world" };
+            }
+
+            export default function Component() {
+              const data = useTypedLoaderData();
+              return <p>{data.hello}</p>;
+            }
+        "#;
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let mut options = CodemodOptions::default();
+        options.data_hooks.add_preset("remix-typedjson").unwrap();
+        let output = codemod(&outdent(input), source_type, &options).unwrap();
+        assert!(output.code.contains("const data = useTypedLoaderData();"));
+        assert!(output.code.contains("Component() {"));
+    }
+
+    fn assert_snapshot(name: &str, preset: &str, input: &str) {
+        let mut options = CodemodOptions::default();
+        options.data_hooks.add_preset(preset).unwrap();
+        assert_codemod_snapshot(name, input, &options);
+    }
+}
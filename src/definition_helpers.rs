@@ -0,0 +1,190 @@
+//! Pass-through helpers that only add types to route exports, like
+//! `export const loader = unstable_defineLoader(async ({ params }) => ...)`
+//! from the Remix 2.9 single fetch APIs. defineRoute infers the same types,
+//! so the function inside them is moved on its own.
+
+use oxc_ast::ast::{Expression, ImportDeclarationSpecifier, Program};
+
+use crate::imports::get_import_declarations;
+
+pub const DEFAULT_DEFINITION_HELPERS: [&str; 4] = [
+    "unstable_defineLoader",
+    "unstable_defineAction",
+    "unstable_defineClientLoader",
+    "unstable_defineClientAction",
+];
+
+#[derive(Debug, Clone)]
+pub struct DefinitionHelpers {
+    pub names: Vec<String>,
+}
+
+impl Default for DefinitionHelpers {
+    fn default() -> Self {
+        Self {
+            names: DEFAULT_DEFINITION_HELPERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl DefinitionHelpers {
+    pub fn add(&mut self, name: &str) {
+        if !self.names.iter().any(|n| n == name) {
+            self.names.push(name.to_owned());
+        }
+    }
+}
+
+/// Definition helpers as they are called in a module
+pub struct DefinitionHelperMatcher<'a> {
+    /// Local names of imported helpers
+    imported: Vec<&'a str>,
+    /// Imported names that aren't helpers, even if their name matches one
+    other_imports: Vec<&'a str>,
+    helpers: &'a DefinitionHelpers,
+}
+
+impl<'a> DefinitionHelperMatcher<'a> {
+    pub fn new(program: &Program<'a>, helpers: &'a DefinitionHelpers) -> Self {
+        let mut imported = vec![];
+        let mut other_imports = vec![];
+        for decl in get_import_declarations(program) {
+            for specifier in decl.specifiers.iter().flatten() {
+                let ImportDeclarationSpecifier::ImportSpecifier(specifier) = specifier else {
+                    continue;
+                };
+                let local = specifier.local.name.as_str();
+                if helpers
+                    .names
+                    .iter()
+                    .any(|name| name == specifier.imported.name().as_str())
+                {
+                    imported.push(local);
+                } else {
+                    other_imports.push(local);
+                }
+            }
+        }
+        Self {
+            imported,
+            other_imports,
+            helpers,
+        }
+    }
+
+    pub fn is_helper(&self, callee: &str) -> bool {
+        self.imported.contains(&callee)
+            || (!self.other_imports.contains(&callee)
+                && self.helpers.names.iter().any(|name| name == callee))
+    }
+
+    /// Function passed to a helper call, like the arrow function in
+    /// `unstable_defineLoader(async () => ...)`
+    pub fn unwrap<'b>(&self, expr: &'b Expression<'a>) -> Option<&'b Expression<'a>> {
+        let Expression::CallExpression(call_expr) = expr else {
+            return None;
+        };
+        let Expression::Identifier(callee) = &call_expr.callee else {
+            return None;
+        };
+        if !self.is_helper(&callee.name) || call_expr.arguments.len() != 1 {
+            return None;
+        }
+        call_expr.arguments[0].as_expression().filter(|arg| {
+            matches!(
+                arg,
+                Expression::ArrowFunctionExpression(_) | Expression::FunctionExpression(_)
+            )
+        })
+    }
+
+    /// Name of the helper a call unwrapped by `unwrap` uses
+    pub fn get_callee<'b>(&self, expr: &'b Expression<'a>) -> Option<&'b str> {
+        self.unwrap(expr)?;
+        match expr {
+            Expression::CallExpression(call_expr) => match &call_expr.callee {
+                Expression::Identifier(callee) => Some(callee.name.as_str()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DefinitionHelpers;
+    use crate::{codemod_models::CodemodOptions, test_utils::assert_codemod_snapshot};
+
+    #[test]
+    fn test_single_fetch_helpers() {
+        let input = r#"
+            import {
+              unstable_defineAction as defineAction,
+              unstable_defineLoader,
+            } from "@remix-run/node";
+            import { unstable_defineClientLoader } from "@remix-run/react";
+
+            export const loader = unstable_defineLoader(async ({ params }) => {
+              return { user: await getUser(params.userId) };
+            });
+
+            export const action = defineAction(async ({ request }) => {
+              const formData = await request.formData();
+              return { ok: formData.has("name") };
+            });
+
+            export const clientLoader = unstable_defineClientLoader(async ({ serverLoader }) => {
+              return await serverLoader();
+            });
+
+            export default function User() {
+              return null;
+            }
+        "#;
+        assert_snapshot("single_fetch_helpers", input, DefinitionHelpers::default());
+    }
+
+    #[test]
+    fn test_helper_still_used() {
+        let input = r#"
+            import { unstable_defineLoader } from "@remix-run/node";
+
+            const getUser = unstable_defineLoader(async ({ params }) => ({ id: params.id }));
+
+            export const loader = unstable_defineLoader(async (args) => {
+              return await getUser(args);
+            });
+        "#;
+        assert_snapshot(
+            "definition_helper_still_used",
+            input,
+            DefinitionHelpers::default(),
+        );
+    }
+
+    #[test]
+    fn test_custom_definition_helper() {
+        let input = r#"
+            import { defineLoader } from "~/utils/route";
+
+            export const loader = defineLoader(function ({ params }) {
+              return { id: params.id };
+            });
+        "#;
+        let mut helpers = DefinitionHelpers::default();
+        helpers.add("defineLoader");
+        assert_snapshot("custom_definition_helper", input, helpers);
+    }
+
+    fn assert_snapshot(name: &str, input: &str, definition_helpers: DefinitionHelpers) {
+        let options = CodemodOptions {
+            definition_helpers,
+            ..Default::default()
+        };
+        assert_codemod_snapshot(name, input, &options);
+    }
+}
@@ -0,0 +1,312 @@
+//! Route exports given as specifiers, like `export { loader }` next to a local
+//! declaration, or `export { loader } from "./users.server"`
+
+use oxc_ast::ast::{
+    BindingPatternKind, Declaration, ExportNamedDeclaration, ExportSpecifier, ModuleExportName,
+    Program, Statement,
+};
+use oxc_semantic::Semantic;
+use oxc_span::GetSpan;
+
+use crate::{
+    codemod::{get_moved_declaration_property, get_moved_statements, rename_exports},
+    codemod_models::{DefineRouteProperty, Diagnostic, StaticProperty},
+    fixer::Fix,
+    function_semantics::{get_declared_name_span, get_export_move_strategy},
+};
+
+#[derive(Default)]
+pub struct MovedSpecifiers<'a> {
+    pub properties: Vec<DefineRouteProperty<'a>>,
+    pub fixes: Vec<Fix<'a>>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Exported and local name of each value specifier exported under an
+/// identifier name
+pub fn get_export_specifiers<'b, 'a>(
+    named_export: &'b ExportNamedDeclaration<'a>,
+) -> Vec<(&'a str, &'a str, &'b ExportSpecifier<'a>)> {
+    if named_export.declaration.is_some() || named_export.export_kind.is_type() {
+        return vec![];
+    }
+    named_export
+        .specifiers
+        .iter()
+        .filter(|specifier| !specifier.export_kind.is_type())
+        .filter_map(|specifier| match &specifier.exported {
+            ModuleExportName::Identifier(exported) => Some((
+                exported.name.as_str(),
+                specifier.local.name().as_str(),
+                specifier,
+            )),
+            ModuleExportName::StringLiteral(_) => None,
+        })
+        .collect()
+}
+
+/// Top-level statement declaring `name`, and the declaration itself
+pub fn find_declaration<'b, 'a>(
+    program: &'b Program<'a>,
+    name: &str,
+) -> Option<(&'b Statement<'a>, &'b Declaration<'a>)> {
+    program.body.iter().find_map(|statement| {
+        let declaration = statement.as_declaration()?;
+        let declares_name = match declaration {
+            Declaration::FunctionDeclaration(decl) => {
+                decl.id.as_ref().is_some_and(|id| id.name == name)
+            }
+            Declaration::VariableDeclaration(decl) => decl.declarations.iter().any(|d| {
+                matches!(&d.id.kind, BindingPatternKind::BindingIdentifier(ident) if ident.name == name)
+            }),
+            _ => false,
+        };
+        declares_name.then_some((statement, declaration))
+    })
+}
+
+/// Declaration of `name` that can move into defineRoute, because nothing but
+/// the export uses it. `export_references` is the number of references the
+/// export itself makes, `1` for `export default Page` and `0` for specifiers.
+pub fn get_movable_declaration<'b, 'a>(
+    program: &'b Program<'a>,
+    semantic: &Semantic,
+    name: &str,
+    export_references: usize,
+) -> Option<(&'b Statement<'a>, &'b Declaration<'a>)> {
+    let symbol_id = semantic.scopes().get_root_binding(name)?;
+    if semantic
+        .symbols()
+        .get_resolved_reference_ids(symbol_id)
+        .len()
+        != export_references
+    {
+        return None;
+    }
+    find_declaration(program, name)
+}
+
+/// Properties for the route exports among the specifiers, and fixes removing
+/// them from the export. Local declarations are moved into defineRoute when
+/// possible, re-exports are imported instead.
+pub fn move_export_specifiers<'a>(
+    named_export: &ExportNamedDeclaration<'a>,
+    program: &Program<'a>,
+    semantic: &Semantic,
+    source_text: &'a str,
+    known_remix_exports: &[&str],
+) -> MovedSpecifiers<'a> {
+    let mut moved = MovedSpecifiers::default();
+    let mut moved_specifiers = vec![];
+    let mut imports = vec![];
+    let moved_statements = get_moved_statements(program);
+
+    for (exported, local, specifier) in get_export_specifiers(named_export) {
+        if !known_remix_exports.contains(&exported) {
+            continue;
+        }
+        let key = rename_exports(Some(exported)).unwrap_or(exported);
+
+        match &named_export.source {
+            Some(source) => {
+                // Imported under the exported name, which is unique in the module
+                if semantic.scopes().get_root_binding(exported).is_some() {
+                    moved.diagnostics.push(Diagnostic::new(
+                        "move-export-specifier",
+                        format!(
+                            "Can't import `{}` from \"{}\", the name is already used in this file",
+                            exported, source.value
+                        ),
+                        specifier.span,
+                    ));
+                    continue;
+                }
+                imports.push(if local == exported {
+                    local.to_owned()
+                } else {
+                    format!("{} as {}", local, exported)
+                });
+                let span = specifier.exported.span();
+                moved
+                    .properties
+                    .push(DefineRouteProperty::StaticProperty(StaticProperty {
+                        key,
+                        value: span.source_text(source_text),
+                        span,
+                    }));
+            }
+            None => {
+                let mut property = None;
+                if let Some((statement, declaration)) = find_declaration(program, local) {
+                    let strategy =
+                        get_export_move_strategy(declaration, semantic, &moved_statements);
+                    let name_span = get_declared_name_span(declaration);
+                    moved
+                        .diagnostics
+                        .extend(strategy.diagnostic(local, name_span));
+                    property = get_moved_declaration_property(
+                        declaration,
+                        local,
+                        key,
+                        source_text,
+                        &strategy,
+                    )
+                    .map(|property| (statement.span(), property));
+                }
+                match property {
+                    Some((statement, property)) => {
+                        moved.properties.push(property);
+                        moved
+                            .fixes
+                            .push(Fix::delete_with_leading_whitespace(statement).with_rule(
+                                "move-export-specifier",
+                                format!("Move `{}` declaration into defineRoute", local),
+                            ));
+                    }
+                    // Still used in this file, or not a function we can move
+                    None => {
+                        let span = specifier.local.span();
+                        moved.properties.push(DefineRouteProperty::StaticProperty(
+                            StaticProperty {
+                                key,
+                                value: span.source_text(source_text),
+                                span,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        moved_specifiers.push(specifier.span);
+    }
+
+    if moved_specifiers.is_empty() {
+        return moved;
+    }
+
+    let source = named_export
+        .source
+        .as_ref()
+        .map(|source| source.span.source_text(source_text));
+    let mut statements = vec![];
+    if let Some(source) = source.filter(|_| !imports.is_empty()) {
+        statements.push(format!(
+            "import {{ {} }} from {};",
+            imports.join(", "),
+            source
+        ));
+    }
+    let remaining = named_export
+        .specifiers
+        .iter()
+        .filter(|specifier| !moved_specifiers.contains(&specifier.span))
+        .map(|specifier| specifier.span.source_text(source_text))
+        .collect::<Vec<_>>();
+    if !remaining.is_empty() {
+        statements.push(match source {
+            Some(source) => format!("export {{ {} }} from {};", remaining.join(", "), source),
+            None => format!("export {{ {} }};", remaining.join(", ")),
+        });
+    }
+
+    let fix = if statements.is_empty() {
+        Fix::delete_with_leading_whitespace(named_export.span)
+    } else {
+        Fix::insert(statements.join("\n"), named_export.span)
+    };
+    moved.fixes.push(fix.with_rule(
+        "move-export-specifier",
+        "Move route exports into defineRoute",
+    ));
+
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{codemod_models::CodemodOptions, test_utils::assert_codemod_snapshot};
+
+    #[test]
+    fn test_export_specifiers() {
+        let input = r#"
+            import { json } from "@remix-run/node";
+            import type { LoaderFunctionArgs } from "@remix-run/node";
+
+            async function loader({ params }: LoaderFunctionArgs) {
+              return json({ id: params.id });
+            }
+
+            const meta = () => [{ title: "User" }];
+
+            function getAction() {
+              return async () => null;
+            }
+            const action = getAction();
+
+            const helper = () => null;
+
+            export { loader, meta as meta, action, helper };
+
+            export default function User() {
+              const data = useLoaderData();
+              return <h1>{data.id}</h1>;
+            }
+        "#;
+        assert_codemod_snapshot("export_specifiers", input, &CodemodOptions::default());
+    }
+
+    #[test]
+    fn test_export_specifiers_still_used() {
+        let input = r#"
+            async function loader() {
+              return { ok: true };
+            }
+
+            export async function clientLoader() {
+              return loader();
+            }
+
+            export { loader };
+
+            export default function Component() {
+              return null;
+            }
+        "#;
+        assert_codemod_snapshot(
+            "export_specifiers_still_used",
+            input,
+            &CodemodOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_export_specifiers_multiple_declarators() {
+        let input = r#"
+            const title = "Users", loader = async () => ({ title });
+
+            export { loader };
+
+            export default function Users() {
+              return null;
+            }
+        "#;
+        assert_codemod_snapshot(
+            "export_specifiers_multiple_declarators",
+            input,
+            &CodemodOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_re_exports() {
+        let input = r#"
+            export { loader, action as action, headers } from "./users.server";
+            export { getMeta as meta } from "./users.meta";
+
+            export default function Users() {
+              return null;
+            }
+        "#;
+        assert_codemod_snapshot("re_exports", input, &CodemodOptions::default());
+    }
+}
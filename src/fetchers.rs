@@ -0,0 +1,276 @@
+//! Typed fetchers. `useFetcher<typeof action>()` points at a route export
+//! that moves into defineRoute, so it reads the type of the route module
+//! instead. URLs that fetchers submit to are checked against the route tree.
+
+use oxc_ast::{
+    ast::{
+        Argument, BindingPatternKind, Expression, ImportDeclarationSpecifier, JSXAttributeItem,
+        JSXAttributeName, JSXAttributeValue, JSXElementName, JSXMemberExpressionObject,
+        ObjectPropertyKind, Program, TSType, TSTypeQueryExprName,
+    },
+    AstKind,
+};
+use oxc_semantic::{Semantic, SymbolId};
+use oxc_span::Span;
+
+use crate::{
+    codemod_models::{Diagnostic, ManifestRoute},
+    fixer::Fix,
+    imports::get_import_declarations,
+    parent_routes::ParentRouteImports,
+    route_imports::resolve_route_import,
+    route_paths::find_route,
+};
+
+const FETCHER_MODULES: [&str; 2] = ["@remix-run/react", "react-router"];
+
+/// Route exports a fetcher can be typed with
+const FETCHER_EXPORTS: [&str; 4] = ["loader", "action", "clientLoader", "clientAction"];
+
+#[derive(Default)]
+pub struct TypedFetchers<'b> {
+    pub fixes: Vec<Fix<'b>>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Type arguments that no longer reference route exports
+    pub replaced: Vec<Span>,
+}
+
+/// `moved_exports` are the bindings of this route's exports that move into
+/// defineRoute
+pub fn type_fetchers<'b>(
+    program: &Program,
+    semantic: &Semantic,
+    moved_exports: &[SymbolId],
+    parent_routes: &mut ParentRouteImports,
+) -> TypedFetchers<'b> {
+    let mut result = TypedFetchers::default();
+
+    let hook_names = get_import_declarations(program)
+        .into_iter()
+        .filter(|decl| FETCHER_MODULES.contains(&decl.source.value.as_str()))
+        .flat_map(|decl| decl.specifiers.iter().flatten())
+        .filter_map(|specifier| match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(s) if s.imported.name() == "useFetcher" => {
+                Some(s.local.name.as_str())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if hook_names.is_empty() || !parent_routes.has_manifest() {
+        return result;
+    }
+
+    let mut fetchers = vec![];
+    for node in semantic.nodes().iter() {
+        let AstKind::CallExpression(call_expr) = node.kind() else {
+            continue;
+        };
+        let Expression::Identifier(callee) = &call_expr.callee else {
+            continue;
+        };
+        if !hook_names.contains(&callee.name.as_str())
+            || semantic.scopes().get_root_binding(&callee.name).is_none()
+        {
+            continue;
+        }
+        if let Some(AstKind::VariableDeclarator(declarator)) =
+            semantic.nodes().parent_kind(node.id())
+        {
+            if let BindingPatternKind::BindingIdentifier(binding) = &declarator.id.kind {
+                fetchers.push(binding.name.as_str());
+            }
+        }
+
+        // `useFetcher<typeof action>()`
+        let Some(type_parameters) = &call_expr.type_parameters else {
+            continue;
+        };
+        let [TSType::TSTypeQuery(query)] = type_parameters.params.as_slice() else {
+            continue;
+        };
+        let TSTypeQueryExprName::IdentifierReference(ident) = &query.expr_name else {
+            continue;
+        };
+        let Some(symbol_id) = semantic.scopes().get_root_binding(&ident.name) else {
+            continue;
+        };
+        let route = if moved_exports.contains(&symbol_id) {
+            parent_routes.own_route()
+        } else {
+            get_imported_route_export(program, symbol_id, parent_routes)
+        };
+        let Some(name) = route.and_then(|route| parent_routes.get(&route.id)) else {
+            continue;
+        };
+
+        parent_routes.replace(type_parameters.span);
+        result.replaced.push(type_parameters.span);
+        result.fixes.push(
+            Fix::insert(format!("<typeof {}>", name), type_parameters.span).with_rule(
+                "typed-fetcher",
+                format!(
+                    "Type `{}` with its route instead of `{}`",
+                    callee.name, ident.name
+                ),
+            ),
+        );
+    }
+
+    let routes = &parent_routes.options().routes;
+    let mut check = |url: &str, span: Span| {
+        // Relative URLs resolve against the route that renders the fetcher
+        if url.starts_with('/') && find_route(routes, url).is_none() {
+            result.diagnostics.push(Diagnostic::new(
+                "fetcher-action",
+                format!("`{}` doesn't match any route", url),
+                span,
+            ));
+        }
+    };
+    for node in semantic.nodes().iter() {
+        match node.kind() {
+            // `<fetcher.Form action="/users/123">`
+            AstKind::JSXOpeningElement(element) => {
+                let JSXElementName::MemberExpression(member) = &element.name else {
+                    continue;
+                };
+                let JSXMemberExpressionObject::Identifier(object) = &member.object else {
+                    continue;
+                };
+                if member.property.name != "Form" || !fetchers.contains(&object.name.as_str()) {
+                    continue;
+                }
+                for attribute in element.attributes.iter() {
+                    let JSXAttributeItem::Attribute(attribute) = attribute else {
+                        continue;
+                    };
+                    if let (
+                        JSXAttributeName::Identifier(name),
+                        Some(JSXAttributeValue::StringLiteral(value)),
+                    ) = (&attribute.name, &attribute.value)
+                    {
+                        if name.name == "action" {
+                            check(&value.value, value.span);
+                        }
+                    }
+                }
+            }
+            // `fetcher.submit(data, { action: "/users/123" })`, `fetcher.load("/users")`
+            AstKind::CallExpression(call_expr) => {
+                let Expression::StaticMemberExpression(member) = &call_expr.callee else {
+                    continue;
+                };
+                if !matches!(&member.object, Expression::Identifier(object) if fetchers.contains(&object.name.as_str()))
+                {
+                    continue;
+                }
+                match (
+                    member.property.name.as_str(),
+                    call_expr.arguments.as_slice(),
+                ) {
+                    ("submit", [_, Argument::ObjectExpression(options)]) => {
+                        for property in options.properties.iter() {
+                            let ObjectPropertyKind::ObjectProperty(property) = property else {
+                                continue;
+                            };
+                            if let Expression::StringLiteral(value) = &property.value {
+                                if property.key.is_specific_static_name("action") {
+                                    check(&value.value, value.span);
+                                }
+                            }
+                        }
+                    }
+                    ("load", [Argument::StringLiteral(value), ..]) => {
+                        check(&value.value, value.span);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+/// Route of an imported route export, like `action as userAction` from
+/// `~/routes/users.$userId`
+fn get_imported_route_export<'s>(
+    program: &Program,
+    symbol_id: SymbolId,
+    parent_routes: &ParentRouteImports<'s, '_>,
+) -> Option<&'s ManifestRoute> {
+    let options = parent_routes.options();
+    let route_file = options.route_file.as_ref()?;
+    get_import_declarations(program)
+        .into_iter()
+        .find_map(|decl| {
+            let imports_export = decl.specifiers.iter().flatten().any(|specifier| {
+                matches!(specifier, ImportDeclarationSpecifier::ImportSpecifier(s)
+                if s.local.symbol_id.get() == Some(symbol_id)
+                    && FETCHER_EXPORTS.contains(&s.imported.name().as_str()))
+            });
+            if !imports_export {
+                return None;
+            }
+            resolve_route_import(&decl.source.value, route_file, &options.routes)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{assert_codemod_snapshot, manifest_options};
+
+    #[test]
+    fn test_typed_fetchers() {
+        let input = r#"
+            import { useFetcher } from "@remix-run/react";
+            import type { action as editAction } from "./users.$userId_.edit";
+
+            export async function action() {
+              return { ok: true };
+            }
+
+            export default function User() {
+              const fetcher = useFetcher<typeof action>();
+              const editFetcher = useFetcher<typeof editAction>();
+              return (
+                <>
+                  <fetcher.Form method="post">{fetcher.data?.ok}</fetcher.Form>
+                  <editFetcher.Form method="post" action="/users/123/edit" />
+                </>
+              );
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("typed_fetchers", input, &manifest_options());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_fetcher_actions() {
+        let input = r#"
+            import { useFetcher } from "@remix-run/react";
+
+            export default function User() {
+              const fetcher = useFetcher();
+              const onClick = () => {
+                fetcher.submit({ name: "Jane" }, { method: "post", action: "/user/123" });
+                fetcher.submit({ name: "Jane" }, { method: "post", action: "/users/123" });
+                fetcher.load("/files/readme.md");
+              };
+              return <fetcher.Form action="/teams/new" onClick={onClick} />;
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("fetcher_actions", input, &manifest_options());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "6:64 `/user/123` doesn't match any route (fetcher-action)",
+                "10:31 `/teams/new` doesn't match any route (fetcher-action)",
+            ]
+        );
+    }
+}
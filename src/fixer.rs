@@ -3,26 +3,36 @@
 //
 // Our modifications:
 // - Add Fix#trim_leading_whitespace
+// - Add Fix#rule_id and Fix#message
+// - Report dropped fixes and the fixes they overlap with, instead of discarding them
+// - Optionally produce a ByteMap of the fixed code, see Fixer#with_source_map
 
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt};
 
 use oxc_span::Span;
 
+use crate::sourcemap::{ByteMap, MappedText};
+
 #[derive(Debug, Clone, Default)]
 pub struct Fix<'a> {
     pub content: Cow<'a, str>,
     pub span: Span,
-    fixed: bool,
+    /// Set with Fix#with_rule, every fix should have one to be reported
+    pub rule_id: Option<&'static str>,
+    pub message: Cow<'a, str>,
+    /// Where the inserted content came from, relative to the start of `content`
+    content_map: Option<ByteMap>,
     trim_leading_whitespace: bool,
 }
 
-#[allow(unused)]
 impl<'a> Fix<'a> {
     pub const fn delete(span: Span) -> Self {
         Self {
             content: Cow::Borrowed(""),
             span,
-            fixed: false,
+            rule_id: None,
+            message: Cow::Borrowed(""),
+            content_map: None,
             trim_leading_whitespace: false,
         }
     }
@@ -31,7 +41,9 @@ impl<'a> Fix<'a> {
         Self {
             content: Cow::Borrowed(""),
             span,
-            fixed: false,
+            rule_id: None,
+            message: Cow::Borrowed(""),
+            content_map: None,
             trim_leading_whitespace: true,
         }
     }
@@ -40,26 +52,105 @@ impl<'a> Fix<'a> {
         Self {
             content: content.into(),
             span,
-            fixed: false,
+            rule_id: None,
+            message: Cow::Borrowed(""),
+            content_map: None,
+            trim_leading_whitespace: false,
+        }
+    }
+
+    pub fn insert_mapped(content: MappedText, span: Span) -> Self {
+        Self {
+            content: Cow::Owned(content.text),
+            span,
+            rule_id: None,
+            message: Cow::Borrowed(""),
+            content_map: Some(content.map),
             trim_leading_whitespace: false,
         }
     }
+
+    /// Attach the rule that produced this fix, used when reporting conflicts
+    pub fn with_rule<T: Into<Cow<'a, str>>>(mut self, rule_id: &'static str, message: T) -> Self {
+        self.rule_id = Some(rule_id);
+        self.message = message.into();
+        self
+    }
+
+    fn info(&self) -> FixInfo {
+        FixInfo {
+            rule_id: self.rule_id,
+            message: self.message.to_string(),
+            span: self.span,
+        }
+    }
+}
+
+/// Owned summary of a fix, detached from the source text lifetime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixInfo {
+    pub rule_id: Option<&'static str>,
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for FixInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rule_id {
+            Some(rule_id) => write!(f, "`{}`", rule_id)?,
+            None => write!(f, "Fix without a rule")?,
+        }
+        write!(
+            f,
+            " at {}..{} ({})",
+            self.span.start, self.span.end, self.message
+        )
+    }
+}
+
+/// A fix that could not be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedFix {
+    pub fix: FixInfo,
+    /// The already applied fix whose span overlaps this one, if that was the reason
+    pub overlaps_with: Option<FixInfo>,
+}
+
+impl fmt::Display for DroppedFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.overlaps_with {
+            Some(other) => write!(f, "{} overlaps {}", self.fix, other),
+            None => write!(f, "{} has an invalid span", self.fix),
+        }
+    }
 }
 
 pub struct FixResult<'a> {
-    pub fixed: bool,
     pub fixed_code: Cow<'a, str>,
-    pub fixes: Vec<Fix<'a>>,
+    pub dropped: Vec<DroppedFix>,
+    /// Maps `fixed_code` back to the source text, see Fixer#with_source_map
+    pub source_map: Option<ByteMap>,
 }
 
 pub struct Fixer<'a> {
     source_text: &'a str,
     fixes: Vec<Fix<'a>>,
+    source_map: bool,
 }
 
 impl<'a> Fixer<'a> {
     pub fn new(source_text: &'a str, fixes: Vec<Fix<'a>>) -> Self {
-        Self { source_text, fixes }
+        Self {
+            source_text,
+            fixes,
+            source_map: false,
+        }
+    }
+
+    /// Also produce a map from the fixed code back to the source text
+    pub fn with_source_map(mut self, yes: bool) -> Self {
+        self.source_map = yes;
+        self
     }
 
     /// # Panics
@@ -67,68 +158,148 @@ impl<'a> Fixer<'a> {
         let source_text = self.source_text;
 
         self.fixes.sort_by_key(|m| m.span);
-        let mut fixed = false;
         let mut output = String::with_capacity(source_text.len());
         let mut last_pos: i64 = -1;
-        self.fixes.iter_mut().for_each(|fix| {
+        let mut last_applied: Option<usize> = None;
+        let mut dropped = vec![];
+        let mut map = ByteMap::default();
+        for i in 0..self.fixes.len() {
+            let fix = &self.fixes[i];
+            debug_assert!(fix.rule_id.is_some(), "Fix without a rule: {:?}", fix);
             let start = fix.span.start;
             let end = fix.span.end;
             if start > end {
-                return;
+                dropped.push(DroppedFix {
+                    fix: fix.info(),
+                    overlaps_with: None,
+                });
+                continue;
             }
-            if i64::from(start) <= last_pos {
-                return;
+            // Fixes that only touch each other (or insert at the same position) don't conflict
+            if i64::from(start) < last_pos {
+                dropped.push(DroppedFix {
+                    fix: fix.info(),
+                    overlaps_with: last_applied.map(|j| self.fixes[j].info()),
+                });
+                continue;
             }
 
             let offset = usize::try_from(last_pos.max(0)).ok().unwrap();
 
             let start = if fix.trim_leading_whitespace {
-                get_position_of_nearest_leading_newline(&source_text, start)
+                // Never trim into the text replaced by the previous fix
+                get_position_of_nearest_leading_newline(source_text, start).max(offset as u32)
             } else {
                 start
             };
 
-            if start != fix.span.start {
-                fix.span.start = start;
-            }
-
             // Copy the text before the current fix
             output.push_str(&source_text[offset..start as usize]);
             // Apply the current fix
             output.push_str(&fix.content);
 
-            last_pos = i64::from(end);
+            if self.source_map {
+                map.push(Some(offset as u32), start - offset as u32);
+                match &fix.content_map {
+                    Some(content_map) => map.extend(content_map),
+                    None => map.push(None, fix.content.len() as u32),
+                }
+            }
 
-            fix.fixed = true;
-            fixed = true;
-        });
+            last_pos = i64::from(end);
+            last_applied = Some(i);
+        }
 
         // Copy the text after the last fix
         let offset = usize::try_from(last_pos.max(0)).ok().unwrap();
         output.push_str(&source_text[offset..]);
+        map.push(Some(offset as u32), (source_text.len() - offset) as u32);
 
-        let mut fixes = self
-            .fixes
-            .into_iter()
-            .filter(|fix| !fix.fixed)
-            .collect::<Vec<_>>();
-        fixes.sort_by_key(|fix| (fix.span.start, fix.span.end));
         FixResult {
-            fixed,
             fixed_code: Cow::Owned(output),
-            fixes,
+            dropped,
+            source_map: self.source_map.then_some(map),
         }
     }
 }
 
-/// Find nearest \n before the given position
+/// Find nearest \n (or \r\n) before the given position
 fn get_position_of_nearest_leading_newline(s: &str, starting_position: u32) -> u32 {
     let mut pos = starting_position;
     while pos > 0 {
         pos -= 1;
         if s.as_bytes()[pos as usize] == b'\n' {
+            if pos > 0 && s.as_bytes()[pos as usize - 1] == b'\r' {
+                return pos - 1;
+            }
             return pos;
         }
     }
     starting_position
 }
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::Span;
+
+    use super::{Fix, Fixer};
+
+    #[test]
+    fn test_adjacent_fixes() {
+        let result = Fixer::new(
+            "abcdef",
+            vec![
+                Fix::delete(Span::new(0, 3)).with_rule("a", "delete abc"),
+                Fix::delete(Span::new(3, 6)).with_rule("b", "delete def"),
+                Fix::insert("xyz", Span::new(6, 6)).with_rule("c", "insert xyz"),
+            ],
+        )
+        .fix();
+        assert_eq!(result.fixed_code, "xyz");
+        assert!(result.dropped.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_fixes() {
+        let result = Fixer::new(
+            "abcdef",
+            vec![
+                Fix::delete(Span::new(0, 4)).with_rule("a", "delete abcd"),
+                Fix::insert("xyz", Span::new(2, 5)).with_rule("b", "replace cde"),
+            ],
+        )
+        .fix();
+        assert_eq!(result.fixed_code, "ef");
+        assert_eq!(result.dropped.len(), 1);
+        let dropped = &result.dropped[0];
+        assert_eq!(dropped.fix.rule_id, Some("b"));
+        assert_eq!(dropped.fix.span, Span::new(2, 5));
+        let overlaps_with = dropped.overlaps_with.as_ref().unwrap();
+        assert_eq!(overlaps_with.rule_id, Some("a"));
+        assert_eq!(overlaps_with.span, Span::new(0, 4));
+    }
+
+    #[test]
+    fn test_trim_leading_whitespace_after_fix() {
+        let result = Fixer::new(
+            "a\n; b;\n",
+            vec![
+                Fix::delete(Span::new(0, 3)).with_rule("a", "delete a"),
+                Fix::delete_with_leading_whitespace(Span::new(4, 6)).with_rule("b", "delete b"),
+            ],
+        )
+        .fix();
+        assert_eq!(result.fixed_code, "\n");
+        assert!(result.dropped.is_empty());
+    }
+
+    #[test]
+    fn test_trim_leading_crlf() {
+        let result = Fixer::new(
+            "a;\r\nb;\r\n",
+            vec![Fix::delete_with_leading_whitespace(Span::new(4, 6)).with_rule("b", "delete b")],
+        )
+        .fix();
+        assert_eq!(result.fixed_code, "a;\r\n");
+    }
+}
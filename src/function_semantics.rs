@@ -0,0 +1,278 @@
+//! Check that moving a function into defineRoute doesn't change what `this`,
+//! `arguments`, `new.target` and the function's own name refer to
+
+use oxc_ast::{
+    ast::{BindingIdentifier, BindingPatternKind, Declaration, Expression},
+    AstKind,
+};
+use oxc_semantic::{ScopeId, Semantic};
+use oxc_span::{GetSpan, Span};
+
+use crate::codemod_models::Diagnostic;
+
+/// How a declared function can move into defineRoute without changing what it does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveStrategy {
+    /// As an object method
+    Method,
+    /// As a property holding the original function, because turning it into
+    /// a method would rebind the listed names
+    Function(Vec<String>),
+    /// Kept as a local binding that the property refers to, because the
+    /// function refers to the variable it's assigned to
+    LocalBinding(String),
+    /// Kept as a local binding, because other code in the file uses it
+    UsedElsewhere,
+}
+
+impl MoveStrategy {
+    pub fn is_local_binding(&self) -> bool {
+        matches!(
+            self,
+            MoveStrategy::LocalBinding(_) | MoveStrategy::UsedElsewhere
+        )
+    }
+
+    /// Why the function can't be a method, like "uses `this`"
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            MoveStrategy::Method | MoveStrategy::UsedElsewhere => None,
+            MoveStrategy::Function(names) => Some(format!("uses {}", names.join(", "))),
+            MoveStrategy::LocalBinding(binding) => Some(format!("refers to `{}`", binding)),
+        }
+    }
+
+    /// Why `name` wasn't turned into a method, if it wasn't
+    pub fn diagnostic(&self, name: &str, span: Span) -> Option<Diagnostic> {
+        let outcome = match self {
+            MoveStrategy::Method | MoveStrategy::UsedElsewhere => return None,
+            MoveStrategy::Function(_) => "it was moved as a function instead of a method",
+            MoveStrategy::LocalBinding(_) => "it was kept as a local binding",
+        };
+        Some(Diagnostic::new(
+            "preserve-function-semantics",
+            format!("`{}` {}, so {}", name, self.reason()?, outcome),
+            span,
+        ))
+    }
+}
+
+pub fn get_move_strategy(declaration: &Declaration, semantic: &Semantic) -> MoveStrategy {
+    match declaration {
+        Declaration::FunctionDeclaration(func) => {
+            let mut names = get_rebound_names(semantic, func.span, func.scope_id.get(), false);
+            // The declaration name isn't bound inside methods, but it is in
+            // named function expressions
+            if let Some(id) = &func.id {
+                if is_referenced_within(semantic, id, func.span) {
+                    names.push(format!("its own name `{}`", id.name));
+                }
+            }
+            function_or_method(names)
+        }
+        Declaration::VariableDeclaration(decl) if decl.declarations.len() == 1 => {
+            let declarator = &decl.declarations[0];
+            let Some(init) = &declarator.init else {
+                return MoveStrategy::Method;
+            };
+            if let BindingPatternKind::BindingIdentifier(binding) = &declarator.id.kind {
+                if is_referenced_within(semantic, binding, init.span()) {
+                    return MoveStrategy::LocalBinding(binding.name.to_string());
+                }
+            }
+            match init {
+                Expression::FunctionExpression(func) => {
+                    let mut names =
+                        get_rebound_names(semantic, func.span, func.scope_id.get(), false);
+                    if let Some(id) = &func.id {
+                        if is_referenced_within(semantic, id, func.span) {
+                            names.push(format!("its own name `{}`", id.name));
+                        }
+                    }
+                    function_or_method(names)
+                }
+                Expression::ArrowFunctionExpression(arrow_func) => {
+                    function_or_method(get_rebound_names(semantic, arrow_func.span, None, true))
+                }
+                _ => MoveStrategy::Method,
+            }
+        }
+        _ => MoveStrategy::Method,
+    }
+}
+
+/// Like `get_move_strategy`, but keeps the binding when code outside the
+/// declaration uses it, like `SerializeFrom<typeof loader>`. References in
+/// `ignored` spans are about to be removed and don't count.
+pub fn get_export_move_strategy(
+    declaration: &Declaration,
+    semantic: &Semantic,
+    ignored: &[Span],
+) -> MoveStrategy {
+    let span = declaration.span();
+    let used_elsewhere = get_declared_bindings(declaration)
+        .into_iter()
+        .filter_map(|binding| binding.symbol_id.get())
+        .flat_map(|symbol_id| semantic.symbols().get_resolved_reference_ids(symbol_id))
+        .map(|id| semantic.symbols().get_reference(*id).span())
+        .any(|reference| {
+            !contains(span, reference) && !ignored.iter().any(|i| contains(*i, reference))
+        });
+    if used_elsewhere {
+        MoveStrategy::UsedElsewhere
+    } else {
+        get_move_strategy(declaration, semantic)
+    }
+}
+
+pub fn get_declared_bindings<'b, 'a>(
+    declaration: &'b Declaration<'a>,
+) -> Vec<&'b BindingIdentifier<'a>> {
+    match declaration {
+        Declaration::FunctionDeclaration(func) => func.id.iter().collect(),
+        Declaration::VariableDeclaration(decl) => decl
+            .declarations
+            .iter()
+            .filter_map(|declarator| match &declarator.id.kind {
+                BindingPatternKind::BindingIdentifier(binding) => Some(&**binding),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Span of the declared name, for diagnostics
+pub fn get_declared_name_span(declaration: &Declaration) -> Span {
+    match declaration {
+        Declaration::FunctionDeclaration(func) => func.id.as_ref().map_or(func.span, |id| id.span),
+        Declaration::VariableDeclaration(decl) => decl
+            .declarations
+            .first()
+            .map_or(decl.span, |declarator| declarator.id.span()),
+        _ => declaration.span(),
+    }
+}
+
+fn function_or_method(names: Vec<String>) -> MoveStrategy {
+    if names.is_empty() {
+        MoveStrategy::Method
+    } else {
+        MoveStrategy::Function(names)
+    }
+}
+
+/// `this`, `arguments` and `new.target` in the function that would refer to
+/// something else in a method. Arrow functions take them from the module
+/// scope, other functions only differ in `this` and `new.target`.
+fn get_rebound_names(
+    semantic: &Semantic,
+    span: Span,
+    scope_id: Option<ScopeId>,
+    is_arrow: bool,
+) -> Vec<String> {
+    let mut names = vec![];
+    for node in semantic.nodes().iter() {
+        let name = match node.kind() {
+            AstKind::ThisExpression(expr) if contains(span, expr.span) => "`this`",
+            AstKind::MetaProperty(meta)
+                if contains(span, meta.span)
+                    && meta.meta.name == "new"
+                    && meta.property.name == "target" =>
+            {
+                "`new.target`"
+            }
+            AstKind::IdentifierReference(ident)
+                if is_arrow
+                    && contains(span, ident.span)
+                    && ident.name == "arguments"
+                    && semantic.is_reference_to_global_variable(ident) =>
+            {
+                "`arguments`"
+            }
+            _ => continue,
+        };
+
+        // Closest function that binds its own `this`
+        let owner = semantic.scopes().ancestors(node.scope_id()).find(|id| {
+            let flags = semantic.scopes().get_flags(*id);
+            (flags.is_function() && !flags.is_arrow()) || flags.is_top()
+        });
+        let rebound = if is_arrow {
+            owner.is_some_and(|id| semantic.scopes().get_flags(id).is_top())
+        } else {
+            owner.is_some() && owner == scope_id
+        };
+        if rebound && !names.iter().any(|n| n == name) {
+            names.push(name.to_owned());
+        }
+    }
+    names
+}
+
+fn is_referenced_within(semantic: &Semantic, binding: &BindingIdentifier, span: Span) -> bool {
+    binding.symbol_id.get().is_some_and(|symbol_id| {
+        semantic
+            .symbols()
+            .get_resolved_reference_ids(symbol_id)
+            .iter()
+            .any(|id| contains(span, semantic.symbols().get_reference(*id).span()))
+    })
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{codemod_models::CodemodOptions, test_utils::assert_codemod_snapshot};
+
+    #[test]
+    fn test_preserve_function_semantics() {
+        let input = r#"
+            export async function loader({ params }) {
+              if (params.retry) {
+                return loader({ params: {} });
+              }
+              return { ok: true };
+            }
+
+            export const clientLoader = async () => {
+              return { count: arguments.length };
+            };
+
+            export const action = async ({ request }) => {
+              const retry = () => action({ request });
+              return retry;
+            };
+
+            export function meta() {
+              return [{ title: this.title }];
+            }
+
+            export function shouldRevalidate() {
+              return [1, 2].map(function () {
+                return this;
+              });
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot(
+            "preserve_function_semantics",
+            input,
+            &CodemodOptions::default(),
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "1:23 `loader` uses its own name `loader`, so it was moved as a function instead of a method (preserve-function-semantics)",
+                "8:14 `clientLoader` uses `arguments`, so it was moved as a function instead of a method (preserve-function-semantics)",
+                "12:14 `action` refers to `action`, so it was kept as a local binding (preserve-function-semantics)",
+                "17:17 `meta` uses `this`, so it was moved as a function instead of a method (preserve-function-semantics)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Fixes for adding and removing named imports
+
+use oxc_ast::ast::{ImportDeclaration, ImportDeclarationSpecifier, Program, Statement};
+use oxc_semantic::Semantic;
+use oxc_span::{GetSpan, Span};
+
+use crate::fixer::Fix;
+
+/// Fixes that make `names` available from `module`. Names are added to an
+/// existing import declaration of the same kind, or to a new one inserted
+/// after the last import.
+pub fn add_named_imports<'a>(
+    program: &Program,
+    source_text: &str,
+    module: &str,
+    names: &[&str],
+    type_only: bool,
+) -> Vec<Fix<'a>> {
+    let imports = get_import_declarations(program);
+
+    let missing = names
+        .iter()
+        .filter(|name| {
+            !imports
+                .iter()
+                .filter(|decl| decl.source.value == module)
+                .any(|decl| imports_name(decl, name))
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return vec![];
+    }
+
+    let existing = imports.iter().find(|decl| {
+        decl.source.value == module
+            && decl.import_kind.is_type() == type_only
+            && decl
+                .specifiers
+                .as_ref()
+                .is_some_and(|s| s.iter().all(is_named_specifier))
+    });
+
+    let fix = match existing.and_then(|decl| decl.specifiers.as_ref().map(|s| (decl, s))) {
+        Some((_, specifiers)) if !specifiers.is_empty() => {
+            let last = specifiers.last().unwrap().span();
+            Fix::insert(
+                format!(", {}", missing.join(", ")),
+                Span::new(last.end, last.end),
+            )
+        }
+        Some((decl, _)) => {
+            // `import {} from "module"`
+            let open_brace =
+                decl.span.start + source_text[decl.span.start as usize..].find('{').unwrap() as u32;
+            Fix::insert(
+                format!(" {} ", missing.join(", ")),
+                Span::new(open_brace + 1, open_brace + 1),
+            )
+        }
+        None => {
+            let declaration = format!(
+                "import {}{{ {} }} from \"{}\";",
+                if type_only { "type " } else { "" },
+                missing.join(", "),
+                module
+            );
+            match imports.last() {
+                Some(last) => Fix::insert(
+                    format!("\n{}", declaration),
+                    Span::new(last.span.end, last.span.end),
+                ),
+                None => Fix::insert(format!("{}\n\n", declaration), Span::new(0, 0)),
+            }
+        }
+    };
+
+    vec![fix.with_rule(
+        "add-import",
+        format!("Import {} from \"{}\"", missing.join(", "), module),
+    )]
+}
+
+/// Fixes that remove the import of `local_name`, and the whole declaration
+/// if nothing else is imported by it
+pub fn remove_named_import<'a>(
+    program: &Program,
+    source_text: &str,
+    local_name: &str,
+) -> Vec<Fix<'a>> {
+    remove_named_imports(program, source_text, &[local_name])
+}
+
+/// Same as `remove_named_import`, for several names at once so that removals
+/// from the same declaration don't overlap
+pub fn remove_named_imports<'a>(
+    program: &Program,
+    source_text: &str,
+    local_names: &[&str],
+) -> Vec<Fix<'a>> {
+    let mut fixes = vec![];
+
+    for decl in get_import_declarations(program) {
+        let Some(specifiers) = &decl.specifiers else {
+            continue;
+        };
+        let removed = specifiers
+            .iter()
+            .map(|s| local_names.contains(&get_local_name(s)))
+            .collect::<Vec<_>>();
+        if !removed.contains(&true) {
+            continue;
+        }
+
+        let rule = |fix: Fix<'a>| {
+            let names = specifiers
+                .iter()
+                .zip(removed.iter())
+                .filter(|(_, removed)| **removed)
+                .map(|(s, _)| get_local_name(s))
+                .collect::<Vec<_>>();
+            fix.with_rule(
+                "remove-import",
+                format!("Remove unused import of {}", names.join(", ")),
+            )
+        };
+
+        if !removed.contains(&false) {
+            fixes.push(rule(Fix::delete_with_leading_whitespace(decl.span)));
+            continue;
+        }
+
+        let decl_text = &source_text[decl.span.start as usize..decl.span.end as usize];
+        let mut index = 0;
+        while index < specifiers.len() {
+            if !removed[index] {
+                index += 1;
+                continue;
+            }
+            // Remove runs of adjacent specifiers of the same kind at once
+            let first = index;
+            while index + 1 < specifiers.len()
+                && removed[index + 1]
+                && is_named_specifier(&specifiers[index + 1])
+                    == is_named_specifier(&specifiers[first])
+            {
+                index += 1;
+            }
+            let last = index;
+            index += 1;
+
+            let current = &specifiers[first];
+            let start = current.span().start;
+            let end = specifiers[last].span().end;
+            let next = specifiers.get(last + 1).filter(|next| {
+                !removed[last + 1] && is_named_specifier(next) == is_named_specifier(current)
+            });
+            let previous = first
+                .checked_sub(1)
+                .filter(|i| !removed[*i])
+                .map(|i| &specifiers[i])
+                .filter(|previous| is_named_specifier(previous) == is_named_specifier(current));
+
+            let span = if let Some(next) = next {
+                // Remove up to the next specifier, including the comma
+                Span::new(start, next.span().start)
+            } else if let Some(previous) = previous {
+                // Remove from the end of the previous specifier
+                Span::new(previous.span().end, end)
+            } else if is_named_specifier(current) {
+                // `import a, { b } from "module"`, drop the braces along with `b`
+                let previous = specifiers[first - 1].span();
+                let close_brace = decl.span.start + decl_text.find('}').unwrap() as u32;
+                Span::new(previous.end, close_brace + 1)
+            } else {
+                // `import a, { b } from "module"`, drop `a` and the comma
+                let open_brace = decl.span.start + decl_text.find('{').unwrap() as u32;
+                Span::new(start, open_brace)
+            };
+            fixes.push(rule(Fix::delete(span)));
+        }
+    }
+
+    fixes
+}
+
+/// Fixes that swap the named import of `local_name` from `module` for `names`,
+/// or `None` if `local_name` isn't imported from there
+pub fn replace_named_import<'a>(
+    program: &Program,
+    source_text: &str,
+    module: &str,
+    local_name: &str,
+    names: &[&str],
+) -> Option<Vec<Fix<'a>>> {
+    let imports = get_import_declarations(program);
+    let specifier = imports
+        .iter()
+        .filter(|decl| decl.source.value == module && !decl.import_kind.is_type())
+        .flat_map(|decl| decl.specifiers.iter().flatten())
+        .find(|s| is_named_specifier(s) && get_local_name(s) == local_name)?;
+
+    let missing = names
+        .iter()
+        .filter(|name| {
+            !imports
+                .iter()
+                .filter(|decl| decl.source.value == module)
+                .any(|decl| imports_name(decl, name))
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Some(remove_named_import(program, source_text, local_name));
+    }
+
+    Some(vec![Fix::insert(missing.join(", "), specifier.span())
+        .with_rule(
+            "replace-import",
+            format!(
+                "Replace import of {} with {}",
+                local_name,
+                missing.join(", ")
+            ),
+        )])
+}
+
+/// Local names of imports that are only referenced inside `spans`, which
+/// are replaced or removed, so the imports end up unused
+pub fn get_imports_only_used_in<'a>(
+    program: &Program<'a>,
+    semantic: &Semantic,
+    spans: &[Span],
+) -> Vec<&'a str> {
+    get_import_declarations(program)
+        .into_iter()
+        .flat_map(|decl| decl.specifiers.iter().flatten())
+        .filter(|specifier| {
+            let local = match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(s) => &s.local,
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => &s.local,
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => &s.local,
+            };
+            let Some(symbol_id) = local.symbol_id.get() else {
+                return false;
+            };
+            let references = semantic.symbols().get_resolved_reference_ids(symbol_id);
+            !references.is_empty()
+                && references.iter().all(|id| {
+                    let reference = semantic.symbols().get_reference(*id).span();
+                    spans
+                        .iter()
+                        .any(|span| span.start <= reference.start && reference.end <= span.end)
+                })
+        })
+        .map(get_local_name)
+        .collect()
+}
+
+pub fn get_import_declarations<'a, 'b>(program: &'b Program<'a>) -> Vec<&'b ImportDeclaration<'a>> {
+    program
+        .body
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::ImportDeclaration(decl) => Some(&**decl),
+            _ => None,
+        })
+        .collect()
+}
+
+fn imports_name(decl: &ImportDeclaration, name: &str) -> bool {
+    decl.specifiers
+        .as_ref()
+        .is_some_and(|specifiers| specifiers.iter().any(|s| get_local_name(s) == name))
+}
+
+fn is_named_specifier(specifier: &ImportDeclarationSpecifier) -> bool {
+    matches!(specifier, ImportDeclarationSpecifier::ImportSpecifier(_))
+}
+
+fn get_local_name<'a>(specifier: &ImportDeclarationSpecifier<'a>) -> &'a str {
+    match specifier {
+        ImportDeclarationSpecifier::ImportSpecifier(s) => s.local.name.as_str(),
+        ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => s.local.name.as_str(),
+        ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => s.local.name.as_str(),
+    }
+}
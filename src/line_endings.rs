@@ -0,0 +1,72 @@
+//! The codemod works on `\n` line endings only. Files are normalized before
+//! the transform and converted back afterwards, so CRLF files stay CRLF.
+
+use crate::sourcemap::MappedText;
+
+const BOM: &str = "\u{feff}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    pub bom: bool,
+    pub crlf: bool,
+}
+
+impl TextFormat {
+    /// Detect the BOM, and the line ending used by the first line
+    pub fn detect(text: &str) -> Self {
+        Self {
+            bom: text.starts_with(BOM),
+            crlf: text
+                .find('\n')
+                .is_some_and(|pos| text[..pos].ends_with('\r')),
+        }
+    }
+
+    /// Strip the BOM and convert all `\r\n` to `\n` of CRLF files. LF files
+    /// keep the odd `\r\n`, `restore` wouldn't bring it back otherwise.
+    pub fn normalize(&self, text: &str) -> MappedText {
+        let mut output = MappedText::default();
+        let start = if self.bom { BOM.len() } else { 0 };
+        if !self.crlf {
+            output.push_source(&text[start..], start as u32);
+            return output;
+        }
+        let mut offset = start;
+
+        for line in text[start..].split_inclusive('\n') {
+            match line.strip_suffix("\r\n") {
+                Some(content) => {
+                    output.push_source(content, offset as u32);
+                    output.push_source("\n", (offset + content.len() + 1) as u32);
+                }
+                None => output.push_source(line, offset as u32),
+            }
+            offset += line.len();
+        }
+
+        output
+    }
+
+    /// Add the BOM back and convert `\n` to `\r\n` if the file used it
+    pub fn restore(&self, text: &str) -> MappedText {
+        let mut output = MappedText::default();
+        if self.bom {
+            output.push_str(BOM);
+        }
+
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(content) if self.crlf => {
+                    output.push_source(content, offset as u32);
+                    output.push_str("\r");
+                    output.push_source("\n", (offset + content.len()) as u32);
+                }
+                _ => output.push_source(line, offset as u32),
+            }
+            offset += line.len();
+        }
+
+        output
+    }
+}
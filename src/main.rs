@@ -1,16 +1,47 @@
+mod cli;
 mod codemod;
 mod codemod_models;
+mod data_hooks;
+mod definition_helpers;
+mod export_specifiers;
+mod fetchers;
 mod fixer;
+mod function_semantics;
+mod imports;
+mod line_endings;
+mod meta_parents;
+mod package_imports;
+mod parent_routes;
+mod responses;
+mod revert;
+mod route_config;
+mod route_imports;
+mod route_paths;
+mod sourcemap;
+#[cfg(test)]
+mod test_utils;
+mod typed_links;
+mod typegen;
 mod utils;
+mod verify;
 
+use cli::{Args, Command};
+use codemod_models::{CodemodOptions, ManifestRoute};
 use oxc_span::SourceType;
+use route_imports::RouteModule;
 
 use serde_json::Value;
 use spinners::{Spinner, Spinners};
-use std::{fs, process};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
 
 fn main() {
-    let resolved_dir = utils::get_resolved_dir();
+    let args = cli::parse_args();
+    let resolved_dir = utils::get_resolved_dir(args.dir.as_ref());
 
     println!("Working directory: {}", resolved_dir);
 
@@ -20,6 +51,11 @@ fn main() {
 
     let routes_json: Value = serde_json::from_str(&routes_raw).expect("Failed to parse JSON");
 
+    if args.command == Command::RouteConfig {
+        write_route_config(&routes_json, &resolved_dir);
+        return;
+    }
+
     if let Some(array) = routes_json.as_array() {
         let file_paths: Vec<String> = array
             .iter()
@@ -31,8 +67,42 @@ fn main() {
 
         println!("Found {} route files", file_paths.len());
 
+        let root_file = array
+            .iter()
+            .find(|item| item["id"] == "root")
+            .and_then(|item| item["file"].as_str())
+            .and_then(|file| {
+                utils::get_absolute_files(vec![file.to_owned()], &resolved_dir)
+                    .into_iter()
+                    .next()
+            });
+
+        let routes = array
+            .iter()
+            .flat_map(|item| utils::traverse_route_ids(item, None))
+            .collect::<Vec<_>>();
+
+        let mut failed = vec![];
         for file_path in file_paths.iter() {
-            process_file(&file_path);
+            let is_root_route = root_file.as_ref() == Some(file_path);
+            if !process_file(file_path, is_root_route, &routes, &resolved_dir, &args) {
+                failed.push(file_path.clone());
+            }
+        }
+
+        if args.command == Command::Migrate {
+            // Routes that failed keep their exports
+            let migrated = file_paths
+                .iter()
+                .filter(|file| !failed.contains(file))
+                .cloned()
+                .collect::<Vec<_>>();
+            process_route_imports(&migrated, root_file.as_deref(), &resolved_dir, &args);
+        }
+
+        if !failed.is_empty() {
+            eprintln!("Failed to process {} route file(s)", failed.len());
+            process::exit(1);
         }
     } else {
         eprintln!("Failed to parse JSON: expected an array");
@@ -40,18 +110,165 @@ fn main() {
     }
 }
 
-fn process_file(file_path: &str) {
+/// Transform a route file, `false` if that failed and it was left as it was
+fn process_file(
+    file_path: &str,
+    is_root_route: bool,
+    routes: &[ManifestRoute],
+    resolved_dir: &String,
+    args: &Args,
+) -> bool {
     println!("Processing file: {}", file_path);
 
+    let Ok(source_type) = SourceType::from_path(file_path) else {
+        let reason = if file_path.ends_with(".mdx") {
+            "MDX routes aren't supported, move their exports into defineRoute by hand"
+        } else {
+            "not a JavaScript or TypeScript file"
+        };
+        println!("  skipped: {}", reason);
+        return true;
+    };
     let source_text = fs::read_to_string(file_path).unwrap();
-    let source_type = SourceType::from_path(file_path).unwrap();
 
-    let fixed_code = codemod::codemod(&source_text, source_type);
+    let options = CodemodOptions {
+        source_map: args.source_map.is_some(),
+        route_name: Path::new(file_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned()),
+        rewrite_imports: args.rewrite_imports,
+        data_hooks: args.data_hooks.clone(),
+        is_root_route,
+        resource_route: args.resource_route.clone(),
+        definition_helpers: args.definition_helpers.clone(),
+        route_file: routes
+            .iter()
+            .find(|route| {
+                utils::get_absolute_files(vec![route.file.clone()], resolved_dir)
+                    .first()
+                    .is_some_and(|file| file == file_path)
+            })
+            .map(|route| route.file.clone()),
+        routes: routes.to_vec(),
+        typed_links: args.typed_links,
+    };
+
+    let output = match args.command {
+        Command::Migrate => codemod::codemod(&source_text, source_type, &options),
+        Command::Revert => revert::revert(&source_text, source_type, &options),
+        Command::Typegen => typegen::typegen(&source_text, source_type, &options),
+        Command::RouteConfig => unreachable!("Route config doesn't process route files"),
+    };
+
+    match output {
+        Ok(output) => {
+            fs::write(file_path, &output.code).expect("Failed to write file");
+
+            for diagnostic in output.diagnostics.iter() {
+                println!("  warning: {}", diagnostic);
+            }
+
+            if let (Some(byte_map), Some(source_map_output)) = (output.source_map, &args.source_map)
+            {
+                let map_path =
+                    utils::get_source_map_path(file_path, resolved_dir, source_map_output);
+                let file_name = Path::new(file_path).file_name().unwrap().to_string_lossy();
+                let source = utils::get_source_map_source(&map_path, Path::new(file_path));
+                let source_map =
+                    byte_map.to_source_map(&output.code, &source_text, &file_name, &source);
+
+                if let Some(parent) = map_path.parent() {
+                    fs::create_dir_all(parent).expect("Failed to create source map directory");
+                }
+                fs::write(&map_path, source_map.to_string()).expect("Failed to write source map");
+            }
+            true
+        }
+        // Leave the file as it was, other files can still be processed
+        Err(error) => {
+            println!("Failed to process file: {}", file_path);
+            println!("{}", error);
+            false
+        }
+    }
+}
+
+/// Update imports of moved route exports in all modules of the project, like
+/// tests, stories and shared components
+fn process_route_imports(
+    route_files: &[String],
+    root_file: Option<&str>,
+    resolved_dir: &str,
+    args: &Args,
+) {
+    println!("Checking imports of route modules...");
+
+    // Routes `process_file` skipped, like MDX routes, keep their exports
+    let route_files = route_files
+        .iter()
+        .filter(|file| SourceType::from_path(file).is_ok())
+        .map(PathBuf::from)
+        .collect::<HashSet<_>>();
+    let root_file = root_file.map(PathBuf::from);
+    let app_dir = Path::new(resolved_dir).join("app");
+    let options = CodemodOptions::default();
+
+    for file_path in utils::get_project_files(Path::new(resolved_dir)) {
+        let Ok(source_text) = fs::read_to_string(&file_path) else {
+            continue;
+        };
+        let Ok(source_type) = SourceType::from_path(&file_path) else {
+            continue;
+        };
+        let get_route_module = |source: &str| {
+            let resolved = route_imports::resolve_import(source, &file_path, &app_dir)
+                .filter(|resolved| route_files.contains(resolved))?;
+            if root_file.as_ref() == Some(&resolved) {
+                Some(RouteModule::Root)
+            } else {
+                Some(RouteModule::Route)
+            }
+        };
+
+        match route_imports::rewrite_route_imports(
+            &source_text,
+            source_type,
+            &options,
+            args.route_imports,
+            &get_route_module,
+        ) {
+            Ok(output) => {
+                if !output.changed && output.diagnostics.is_empty() {
+                    continue;
+                }
+                println!("Processing imports: {}", file_path.display());
+                if output.changed {
+                    fs::write(&file_path, &output.code).expect("Failed to write file");
+                }
+                for diagnostic in output.diagnostics.iter() {
+                    println!("  warning: {}", diagnostic);
+                }
+            }
+            // Leave the file as it was, other files can still be updated
+            Err(error) => {
+                println!("Failed to update imports: {}", file_path.display());
+                println!("{}", error);
+            }
+        }
+    }
+}
 
-    if let Err(_) = fixed_code {
-        println!("Failed to process file: {}", file_path);
+fn write_route_config(routes_json: &Value, resolved_dir: &String) {
+    let config_path = Path::new(resolved_dir).join("app").join("routes.ts");
+    if config_path.exists() {
+        eprintln!("Route config already exists: {}", config_path.display());
         process::exit(1);
     }
 
-    fs::write(file_path, fixed_code.unwrap()).expect("Failed to write file");
+    fs::write(
+        &config_path,
+        route_config::generate_route_config(routes_json),
+    )
+    .expect("Failed to write route config");
+    println!("Wrote route config: {}", config_path.display());
 }
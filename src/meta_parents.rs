@@ -0,0 +1,143 @@
+//! Parent route types of `meta`, like the `{ "routes/users": typeof usersLoader }`
+//! in `MetaFunction<typeof loader, { "routes/users": typeof usersLoader }>`.
+//! defineRoute infers the route's own data, parent routes are referenced with
+//! a type-only import of their module in its `parents` property.
+
+use oxc_ast::ast::{BindingPatternKind, Declaration, TSSignature, TSType, TSTypeName};
+use oxc_span::{GetSpan, Span};
+
+use crate::{codemod_models::Diagnostic, parent_routes::ParentRouteImports};
+
+/// Value of the `parents` property for a `meta` declaration moving into
+/// defineRoute, `None` if it has no parent route types
+pub fn get_meta_parents(
+    declaration: &Declaration,
+    source_text: &str,
+    parent_routes: &mut ParentRouteImports,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    let members = get_parent_members(declaration)?;
+    if !parent_routes.has_manifest() {
+        return None;
+    }
+
+    let mut entries = vec![];
+    for (key, id, span) in members {
+        let Some(name) = parent_routes.get(&id) else {
+            diagnostics.push(Diagnostic::new(
+                "meta-parents",
+                format!(
+                    "Route `{}` isn't in the routes manifest, `meta` has no types for its match",
+                    id
+                ),
+                span,
+            ));
+            continue;
+        };
+        parent_routes.replace(span);
+        entries.push(format!(
+            "    {}: typeof {};\n",
+            key.source_text(source_text),
+            name
+        ));
+    }
+
+    if entries.is_empty() {
+        return None;
+    }
+    Some(format!("{{}} as {{\n{}  }}", entries.concat()))
+}
+
+/// Key, route id and span of each member of the second `MetaFunction` type argument
+fn get_parent_members(declaration: &Declaration) -> Option<Vec<(Span, String, Span)>> {
+    let Declaration::VariableDeclaration(decl) = declaration else {
+        return None;
+    };
+    let declarator = decl.declarations.first()?;
+    if !matches!(declarator.id.kind, BindingPatternKind::BindingIdentifier(_)) {
+        return None;
+    }
+    let TSType::TSTypeReference(reference) =
+        &declarator.id.type_annotation.as_ref()?.type_annotation
+    else {
+        return None;
+    };
+    if !matches!(&reference.type_name, TSTypeName::IdentifierReference(ident) if ident.name == "MetaFunction")
+    {
+        return None;
+    }
+    let TSType::TSTypeLiteral(literal) = reference.type_parameters.as_ref()?.params.get(1)? else {
+        return None;
+    };
+
+    Some(
+        literal
+            .members
+            .iter()
+            .filter_map(|member| match member {
+                TSSignature::TSPropertySignature(signature) if !signature.computed => Some((
+                    signature.key.span(),
+                    signature.key.static_name()?.to_string(),
+                    signature.span,
+                )),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{assert_codemod_snapshot, manifest_options};
+
+    #[test]
+    fn test_meta_parents() {
+        let input = r#"
+            import type { MetaFunction } from "@remix-run/node";
+            import type { loader as rootLoader } from "~/root";
+            import type { loader as usersLoader } from "./users";
+
+            export async function loader() {
+              return { name: "Jane" };
+            }
+
+            export const meta: MetaFunction<
+              typeof loader,
+              { root: typeof rootLoader; "routes/users": typeof usersLoader }
+            > = ({ data, matches }) => {
+              const users = matches.find((match) => match.id === "routes/users");
+              return [{ title: `${data.name} of ${users.data.count}` }];
+            };
+
+            export default function User() {
+              return null;
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("meta_parents", input, &manifest_options());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_meta_unknown_parent() {
+        let input = r#"
+            import type { MetaFunction } from "@remix-run/node";
+            import type { loader as teamLoader } from "./team";
+
+            export const meta: MetaFunction<unknown, { "routes/team": typeof teamLoader }> = ({
+              matches,
+            }) => [{ title: String(matches.length) }];
+
+            export default function User() {
+              return null;
+            }
+        "#;
+        let diagnostics =
+            assert_codemod_snapshot("meta_unknown_parent", input, &manifest_options());
+        assert_eq!(
+            diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec![
+                "4:44 Route `routes/team` isn't in the routes manifest, `meta` has no types for its match (meta-parents)"
+            ]
+        );
+    }
+}
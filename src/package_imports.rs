@@ -0,0 +1,327 @@
+//! Rewrite `@remix-run/*` imports to their `react-router` / `@react-router/*`
+//! equivalents
+
+use oxc_ast::ast::{ImportDeclaration, ImportDeclarationSpecifier, Program};
+use oxc_span::{GetSpan, Span};
+
+use crate::{codemod_models::Diagnostic, fixer::Fix, imports::get_import_declarations};
+
+/// Where everything from a Remix package goes, unless listed in `SPECIFIER_MAPPINGS`
+const PACKAGE_MAPPINGS: [(&str, &str); 4] = [
+    ("@remix-run/react", "react-router"),
+    ("@remix-run/node", "react-router"),
+    ("@remix-run/cloudflare", "react-router"),
+    ("@remix-run/server-runtime", "react-router"),
+];
+
+/// Module and exported name an export moved to
+type Target = (&'static str, &'static str);
+
+/// Exports that moved somewhere else or were renamed, `None` if they were removed
+#[rustfmt::skip]
+const SPECIFIER_MAPPINGS: [(&str, &str, Option<Target>); 22] = [
+    ("@remix-run/react", "RemixBrowser", Some(("react-router/dom", "HydratedRouter"))),
+    ("@remix-run/react", "RemixServer", Some(("react-router", "ServerRouter"))),
+    ("@remix-run/react", "LiveReload", None),
+    ("@remix-run/react", "unstable_useViewTransitionState", Some(("react-router", "useViewTransitionState"))),
+    ("@remix-run/node", "createFileSessionStorage", Some(("@react-router/node", "createFileSessionStorage"))),
+    ("@remix-run/node", "createReadableStreamFromReadable", Some(("@react-router/node", "createReadableStreamFromReadable"))),
+    ("@remix-run/node", "readableStreamToString", Some(("@react-router/node", "readableStreamToString"))),
+    ("@remix-run/node", "writeAsyncIterableToWritable", Some(("@react-router/node", "writeAsyncIterableToWritable"))),
+    ("@remix-run/node", "writeReadableStreamToWritable", Some(("@react-router/node", "writeReadableStreamToWritable"))),
+    ("@remix-run/node", "installGlobals", None),
+    ("@remix-run/node", "unstable_createFileUploadHandler", None),
+    ("@remix-run/cloudflare", "createWorkersKVSessionStorage", Some(("@react-router/cloudflare", "createWorkersKVSessionStorage"))),
+    ("@remix-run/cloudflare", "createCloudflareKVSessionStorage", Some(("@react-router/cloudflare", "createWorkersKVSessionStorage"))),
+    ("*", "json", None),
+    ("*", "defer", None),
+    ("*", "TypedResponse", None),
+    ("*", "TypedDeferredData", None),
+    ("*", "SerializeFrom", None),
+    ("*", "unstable_parseMultipartFormData", None),
+    ("*", "unstable_composeUploadHandlers", None),
+    ("*", "unstable_createMemoryUploadHandler", None),
+    ("*", "UploadHandler", None),
+];
+
+/// Target module and exported name of a Remix package export, `None` if it
+/// has no equivalent
+fn get_mapping(package: &str, name: &str) -> Option<Target> {
+    let specific = SPECIFIER_MAPPINGS
+        .iter()
+        .find(|(p, n, _)| (*p == package || *p == "*") && *n == name);
+    match specific {
+        Some((_, _, target)) => *target,
+        None => PACKAGE_MAPPINGS
+            .iter()
+            .find(|(p, _)| *p == package)
+            .map(|(_, target)| (*target, "")),
+    }
+}
+
+/// Named imports that end up in the same declaration
+struct ImportGroup<'b, 'a> {
+    module: &'static str,
+    type_only: bool,
+    specifiers: Vec<String>,
+    /// Declaration that gets replaced with this group
+    anchor: usize,
+    /// Existing declaration from the target module to append to instead
+    existing: Option<&'b ImportDeclaration<'a>>,
+}
+
+pub fn rewrite_package_imports<'a>(
+    program: &Program,
+    source_text: &str,
+) -> (Vec<Fix<'a>>, Vec<Diagnostic>) {
+    let declarations = get_import_declarations(program);
+    let mut fixes = vec![];
+    let mut diagnostics = vec![];
+    let mut groups: Vec<ImportGroup> = vec![];
+    // Specifiers without an equivalent, kept in the original declaration,
+    // and whether they are named
+    let mut leftovers: Vec<Vec<(bool, String)>> = vec![vec![]; declarations.len()];
+    let mut rewritten = vec![false; declarations.len()];
+
+    for (index, decl) in declarations.iter().enumerate() {
+        let package = decl.source.value.as_str();
+        if !PACKAGE_MAPPINGS.iter().any(|(p, _)| *p == package) {
+            continue;
+        }
+        let Some(specifiers) = &decl.specifiers else {
+            continue;
+        };
+        rewritten[index] = true;
+
+        for specifier in specifiers.iter() {
+            let ImportDeclarationSpecifier::ImportSpecifier(specifier) = specifier else {
+                diagnostics.push(Diagnostic::new(
+                    "rewrite-package-imports",
+                    format!(
+                        "`{}` from \"{}\" has no react-router equivalent, import named exports instead",
+                        specifier.span().source_text(source_text),
+                        package
+                    ),
+                    specifier.span(),
+                ));
+                leftovers[index]
+                    .push((false, specifier.span().source_text(source_text).to_owned()));
+                continue;
+            };
+
+            let imported = specifier.imported.name().as_str();
+            let Some((module, name)) = get_mapping(package, imported) else {
+                diagnostics.push(Diagnostic::new(
+                    "rewrite-package-imports",
+                    format!(
+                        "`{}` from \"{}\" has no react-router equivalent",
+                        imported, package
+                    ),
+                    specifier.span,
+                ));
+                leftovers[index].push((true, specifier.span.source_text(source_text).to_owned()));
+                continue;
+            };
+            let name = if name.is_empty() { imported } else { name };
+
+            let local = specifier.local.name.as_str();
+            let mut text = if specifier.import_kind.is_type() {
+                String::from("type ")
+            } else {
+                String::new()
+            };
+            text.push_str(name);
+            if local != name {
+                text.push_str(&format!(" as {}", local));
+            }
+
+            let type_only = decl.import_kind.is_type();
+            match groups
+                .iter_mut()
+                .find(|g| g.module == module && g.type_only == type_only)
+            {
+                Some(group) => {
+                    if !group.specifiers.contains(&text) {
+                        group.specifiers.push(text);
+                    }
+                }
+                None => groups.push(ImportGroup {
+                    module,
+                    type_only,
+                    specifiers: vec![text],
+                    anchor: index,
+                    existing: declarations.iter().copied().find(|d| {
+                        d.source.value == module
+                            && d.import_kind.is_type() == type_only
+                            && d.specifiers.as_ref().is_some_and(|s| {
+                                !s.is_empty()
+                                    && s.iter().all(|s| {
+                                        matches!(s, ImportDeclarationSpecifier::ImportSpecifier(_))
+                                    })
+                            })
+                    }),
+                }),
+            }
+        }
+    }
+
+    for group in groups.iter() {
+        let Some(existing) = group.existing else {
+            continue;
+        };
+        let specifiers = existing.specifiers.as_ref().unwrap();
+        let new_specifiers = group
+            .specifiers
+            .iter()
+            .filter(|text| {
+                !specifiers
+                    .iter()
+                    .any(|s| s.span().source_text(source_text) == text.as_str())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if new_specifiers.is_empty() {
+            continue;
+        }
+        let last = specifiers.last().unwrap().span();
+        fixes.push(rule(Fix::insert(
+            format!(", {}", new_specifiers.join(", ")),
+            Span::new(last.end, last.end),
+        )));
+    }
+
+    for (index, decl) in declarations.iter().enumerate() {
+        if !rewritten[index] {
+            continue;
+        }
+
+        let quote = &decl.source.span.source_text(source_text)[..1];
+        let mut replacement = groups
+            .iter()
+            .filter(|g| g.anchor == index && g.existing.is_none())
+            .map(|g| {
+                format!(
+                    "import {}{{ {} }} from {}{}{};",
+                    if g.type_only { "type " } else { "" },
+                    g.specifiers.join(", "),
+                    quote,
+                    g.module,
+                    quote
+                )
+            })
+            .collect::<Vec<_>>();
+        if !leftovers[index].is_empty() {
+            replacement.push(format_leftovers(decl, &leftovers[index], source_text));
+        }
+
+        let fix = if replacement.is_empty() {
+            Fix::delete_with_leading_whitespace(decl.span)
+        } else {
+            Fix::insert(replacement.join("\n"), decl.span)
+        };
+        fixes.push(rule(fix));
+    }
+
+    (fixes, diagnostics)
+}
+
+fn rule(fix: Fix) -> Fix {
+    fix.with_rule(
+        "rewrite-package-imports",
+        "Import from react-router instead of @remix-run packages",
+    )
+}
+
+/// The original declaration with only the specifiers we couldn't move
+fn format_leftovers(
+    decl: &ImportDeclaration,
+    leftovers: &[(bool, String)],
+    source_text: &str,
+) -> String {
+    let mut clauses = leftovers
+        .iter()
+        .filter(|(named, _)| !named)
+        .map(|(_, text)| text.clone())
+        .collect::<Vec<_>>();
+    let named = leftovers
+        .iter()
+        .filter(|(named, _)| *named)
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>();
+    if !named.is_empty() {
+        clauses.push(format!("{{ {} }}", named.join(", ")));
+    }
+    format!(
+        "import {}{} from {};",
+        if decl.import_kind.is_type() {
+            "type "
+        } else {
+            ""
+        },
+        clauses.join(", "),
+        decl.source.span.source_text(source_text)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{codemod_models::CodemodOptions, test_utils::assert_codemod_snapshot};
+
+    #[test]
+    fn test_rewrite_package_imports() {
+        let input = r#"
+            import type { LoaderFunctionArgs, MetaFunction } from "@remix-run/node";
+            import { json, redirect, createFileSessionStorage } from "@remix-run/node";
+            import { Link as RemixLink, useLoaderData, type ShouldRevalidateFunction } from "@remix-run/react";
+            import { useNavigate } from "react-router";
+            import { z } from "zod";
+
+            export const meta: MetaFunction = () => [{ title: "Users" }];
+
+            export async function loader({ request }: LoaderFunctionArgs) {
+              if (!request.url) throw json("Not found", { status: 404 });
+              if (request.url === "/") throw redirect("/users");
+              return json({ storage: createFileSessionStorage, z });
+            }
+
+            export default function Users() {
+              const data = useLoaderData<typeof loader>();
+              const navigate = useNavigate();
+              return <RemixLink to="/" onClick={() => navigate("/")}>{data.storage.name}</RemixLink>;
+            }
+        "#;
+        let diagnostics =
+            assert_codemod_snapshot("rewrite_package_imports", input, &rewrite_imports_options());
+        assert_eq!(
+            diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec![
+                "2:10 `json` from \"@remix-run/node\" has no react-router equivalent (rewrite-package-imports)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_package_imports_entry() {
+        let input = r#"
+            import { RemixBrowser } from "@remix-run/react";
+            import * as remix from "@remix-run/react";
+            import { hydrateRoot } from "react-dom/client";
+
+            hydrateRoot(document, <RemixBrowser />);
+            console.log(remix);
+        "#;
+        let diagnostics = assert_codemod_snapshot(
+            "rewrite_package_imports_entry",
+            input,
+            &rewrite_imports_options(),
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    fn rewrite_imports_options() -> CodemodOptions {
+        CodemodOptions {
+            rewrite_imports: true,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Typed references to other routes of the routes manifest, by their id.
+//! Route modules are imported type-only, e.g. `import type rootRoute from "../root"`,
+//! so that nothing of them ends up in the bundle of the importing route.
+
+use oxc_ast::{
+    ast::{Argument, Expression, ImportDeclarationSpecifier, Program},
+    AstKind,
+};
+use oxc_semantic::Semantic;
+use oxc_span::{GetSpan, Span};
+
+use crate::{
+    codemod_models::{CodemodOptions, Diagnostic, ManifestRoute},
+    fixer::Fix,
+    imports::{get_import_declarations, get_imports_only_used_in, remove_named_imports},
+    route_imports::{get_relative_import, get_route_binding_name},
+};
+
+const ROUTE_LOADER_DATA_MODULES: [&str; 2] = ["@remix-run/react", "react-router"];
+
+/// Route modules imported to reference them in types
+pub struct ParentRouteImports<'s, 'a> {
+    options: &'s CodemodOptions,
+    semantic: &'s Semantic<'a>,
+    /// Route id, binding name and import source
+    imports: Vec<(String, String, String)>,
+    /// Types now read from the imported modules, imports only used in them go away
+    replaced: Vec<Span>,
+}
+
+impl<'s, 'a> ParentRouteImports<'s, 'a> {
+    pub fn new(options: &'s CodemodOptions, semantic: &'s Semantic<'a>) -> Self {
+        Self {
+            options,
+            semantic,
+            imports: vec![],
+            replaced: vec![],
+        }
+    }
+
+    pub fn options(&self) -> &'s CodemodOptions {
+        self.options
+    }
+
+    /// Whether the routes manifest is known, without it no id can be checked
+    pub fn has_manifest(&self) -> bool {
+        self.options.route_file.is_some()
+    }
+
+    /// The route being transformed
+    pub fn own_route(&self) -> Option<&'s ManifestRoute> {
+        let route_file = self.options.route_file.as_ref()?;
+        self.options
+            .routes
+            .iter()
+            .find(|route| route.file == *route_file)
+    }
+
+    /// Binding name of the imported route module, `None` if the id isn't in
+    /// the routes manifest
+    pub fn get(&mut self, id: &str) -> Option<String> {
+        if let Some((_, name, _)) = self.imports.iter().find(|(i, _, _)| i == id) {
+            return Some(name.clone());
+        }
+        let route_file = self.options.route_file.as_ref()?;
+        let route = self.options.routes.iter().find(|route| route.id == id)?;
+        let source = get_relative_import(route_file, &route.file);
+
+        let name = get_route_binding_name(&source);
+        let mut unique = name.clone();
+        let mut counter = 2;
+        while self.semantic.scopes().get_root_binding(&unique).is_some()
+            || self.imports.iter().any(|(_, n, _)| *n == unique)
+        {
+            unique = format!("{}{}", name, counter);
+            counter += 1;
+        }
+        self.imports.push((id.to_owned(), unique.clone(), source));
+        Some(unique)
+    }
+
+    /// Mark a type as replaced by a reference to an imported route module
+    pub fn replace(&mut self, span: Span) {
+        self.replaced.push(span);
+    }
+
+    /// Fixes adding the route module imports, and removing imports that
+    /// were only used in the replaced types, like `loader as rootLoader`
+    pub fn into_fixes<'b>(self, program: &Program, source_text: &str) -> Vec<Fix<'b>> {
+        let mut fixes = vec![];
+        if self.imports.is_empty() {
+            return fixes;
+        }
+
+        let declarations = self
+            .imports
+            .iter()
+            .map(|(_, name, source)| format!("import type {} from \"{}\";", name, source))
+            .collect::<Vec<_>>();
+        let ids = self
+            .imports
+            .iter()
+            .map(|(id, _, _)| format!("`{}`", id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fix = match get_import_declarations(program).last() {
+            Some(last) => Fix::insert(
+                format!("\n{}", declarations.join("\n")),
+                Span::new(last.span.end, last.span.end),
+            ),
+            None => Fix::insert(format!("{}\n\n", declarations.join("\n")), Span::new(0, 0)),
+        };
+        fixes.push(fix.with_rule("parent-routes", format!("Import the {} routes", ids)));
+
+        let unused = get_imports_only_used_in(program, self.semantic, &self.replaced);
+        if !unused.is_empty() {
+            fixes.extend(remove_named_imports(program, source_text, &unused));
+        }
+
+        fixes
+    }
+}
+
+/// Type `useRouteLoaderData("root")` calls with the module of the route they
+/// read, as `useRouteLoaderData<typeof rootRoute>("root")`
+pub fn type_route_loader_data<'b>(
+    program: &Program,
+    semantic: &Semantic,
+    parent_routes: &mut ParentRouteImports,
+) -> (Vec<Fix<'b>>, Vec<Diagnostic>) {
+    let mut fixes = vec![];
+    let mut diagnostics = vec![];
+
+    let hook_names = get_import_declarations(program)
+        .into_iter()
+        .filter(|decl| ROUTE_LOADER_DATA_MODULES.contains(&decl.source.value.as_str()))
+        .flat_map(|decl| decl.specifiers.iter().flatten())
+        .filter_map(|specifier| match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(s)
+                if s.imported.name() == "useRouteLoaderData" =>
+            {
+                Some(s.local.name.as_str())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if hook_names.is_empty() || !parent_routes.has_manifest() {
+        return (fixes, diagnostics);
+    }
+
+    for node in semantic.nodes().iter() {
+        let AstKind::CallExpression(call_expr) = node.kind() else {
+            continue;
+        };
+        let Expression::Identifier(callee) = &call_expr.callee else {
+            continue;
+        };
+        if !hook_names.contains(&callee.name.as_str())
+            || semantic.scopes().get_root_binding(&callee.name).is_none()
+        {
+            continue;
+        }
+        // Ids built at runtime can't be checked
+        let Some(Argument::StringLiteral(id)) = call_expr.arguments.first() else {
+            continue;
+        };
+        // The route reads its own data with `useLoaderData`
+        if parent_routes
+            .own_route()
+            .is_some_and(|route| route.id == id.value.as_str())
+        {
+            continue;
+        }
+        let Some(name) = parent_routes.get(&id.value) else {
+            diagnostics.push(Diagnostic::new(
+                "route-loader-data",
+                format!(
+                    "Route `{}` isn't in the routes manifest, `{}` returns undefined",
+                    id.value, callee.name
+                ),
+                id.span,
+            ));
+            continue;
+        };
+
+        let type_arguments = format!("<typeof {}>", name);
+        let fix = match &call_expr.type_parameters {
+            Some(type_parameters) => {
+                parent_routes.replace(type_parameters.span);
+                Fix::insert(type_arguments, type_parameters.span)
+            }
+            None => Fix::insert(
+                type_arguments,
+                Span::new(call_expr.callee.span().end, call_expr.callee.span().end),
+            ),
+        };
+        fixes.push(fix.with_rule(
+            "route-loader-data",
+            format!("Type `{}(\"{}\")` with its route", callee.name, id.value),
+        ));
+    }
+
+    (fixes, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{assert_codemod_snapshot, manifest_options};
+
+    #[test]
+    fn test_route_loader_data() {
+        let input = r#"
+            import { useRouteLoaderData } from "@remix-run/react";
+            import type { loader as rootLoader } from "~/root";
+
+            export default function User() {
+              const root = useRouteLoaderData<typeof rootLoader>("root");
+              const users = useRouteLoaderData("routes/users");
+              const user = useRouteLoaderData("routes/users.$userId");
+              return <h1>{root.user.name} {users.length} {user.id}</h1>;
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("route_loader_data", input, &manifest_options());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_route_loader_data_unknown_id() {
+        let input = r#"
+            import { useRouteLoaderData as useParentData } from "@remix-run/react";
+
+            export default function User() {
+              const data = useParentData("routes/user");
+              const other = useParentData(getParentId());
+              return <h1>{data.name} {other.name}</h1>;
+            }
+        "#;
+        let diagnostics =
+            assert_codemod_snapshot("route_loader_data_unknown_id", input, &manifest_options());
+        assert_eq!(
+            diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec![
+                "4:30 Route `routes/user` isn't in the routes manifest, `useParentData` returns undefined (route-loader-data)"
+            ]
+        );
+    }
+}
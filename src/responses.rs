@@ -0,0 +1,616 @@
+//! Unwrap `json()`, `defer()` and `typedjson()` in loader and action returns,
+//! so that loaders return plain objects the route data types can be inferred from.
+//! Which helpers get unwrapped is configured with `DataHooks`.
+
+use oxc_ast::{
+    ast::{
+        ArrowFunctionExpression, BindingPatternKind, CallExpression, Declaration, Expression,
+        FormalParameters, Function, FunctionBody, ImportDeclarationSpecifier, ObjectPropertyKind,
+        Program, ReturnStatement, Statement,
+    },
+    visit::walk,
+    AstKind, Visit,
+};
+use oxc_semantic::{ScopeFlags, Semantic};
+use oxc_span::{GetSpan, Span};
+
+use crate::{
+    codemod::{get_define_route_object, get_indentation},
+    data_hooks::{DataHooks, RUNTIME_MODULES},
+    export_specifiers::{find_declaration, get_export_specifiers},
+    fixer::Fix,
+    imports::{
+        add_named_imports, get_import_declarations, remove_named_imports, replace_named_import,
+    },
+};
+
+/// Where `data()` comes from when the helpers were imported from elsewhere
+const DEFAULT_DATA_MODULE: &str = "@remix-run/node";
+
+/// Module that exports `data()` under that name, the Remix packages only have
+/// `unstable_data()`
+const DATA_MODULE: &str = "react-router";
+
+/// Local name for `data()` when the file already has a `data` binding,
+/// which is a common name for loader results
+const DATA_ALIAS: &str = "responseData";
+
+struct ResponseHelper<'a> {
+    local: &'a str,
+    module: &'a str,
+}
+
+/// A `json(value, init)` call to unwrap
+struct ResponseCall {
+    helper: usize,
+    span: Span,
+    callee: Span,
+    value: Span,
+    value_is_object: bool,
+    /// Status and headers, if the call has a second argument
+    init: Option<ResponseInit>,
+    /// `return json(...)`, or `None` for arrow functions like `() => json(...)`
+    return_statement: Option<Span>,
+    /// The return statement is the body of an `if` or a loop, like
+    /// `if (!user) return json(...)`, so more statements need braces
+    braceless: bool,
+}
+
+enum ResponseInit {
+    /// Only a status, and header values by name, that can be set on `response`
+    Mutation {
+        status: Option<Span>,
+        headers: Vec<(String, Span)>,
+    },
+    Other,
+}
+
+pub fn unwrap_response_helpers<'a>(
+    program: &Program,
+    semantic: &Semantic,
+    source_text: &str,
+    data_hooks: &DataHooks,
+    rewrite_imports: bool,
+) -> Vec<Fix<'a>> {
+    let helpers = get_response_helpers(program, data_hooks);
+    if helpers.is_empty() {
+        return vec![];
+    }
+
+    let mut fixes = vec![];
+    let mut unwrapped = vec![0; helpers.len()];
+    // Modules `data()` is needed from
+    let mut data_modules = vec![];
+    let data_name = if semantic
+        .symbols()
+        .names
+        .iter()
+        .any(|name| name.as_str() == "data")
+    {
+        DATA_ALIAS
+    } else {
+        "data"
+    };
+
+    for (params, body, expression) in get_loader_functions(program) {
+        let response = get_response_binding(params);
+
+        let mut collector = ReturnCollector {
+            helpers: &helpers,
+            calls: vec![],
+        };
+        match expression {
+            Some(expression) => {
+                if let Expression::CallExpression(call_expr) = expression {
+                    collector
+                        .calls
+                        .extend(get_response_call(call_expr, &helpers, None, false));
+                }
+            }
+            None => collector.visit_function_body(body),
+        }
+
+        for call in collector.calls {
+            unwrapped[call.helper] += 1;
+            let value = call.value.source_text(source_text);
+
+            let fix = match (call.init, call.return_statement, response) {
+                (None, _, _) => {
+                    if call.return_statement.is_none() && call.value_is_object {
+                        Fix::insert(format!("({})", value), call.span)
+                    } else {
+                        Fix::insert(value.to_owned(), call.span)
+                    }
+                }
+                (
+                    Some(ResponseInit::Mutation { status, headers }),
+                    Some(return_statement),
+                    Some(response),
+                ) => {
+                    let indentation =
+                        " ".repeat(get_indentation(source_text, return_statement.start));
+                    let mut statements = vec![];
+                    if let Some(status) = status {
+                        statements.push(format!(
+                            "{}.status = {};",
+                            response,
+                            status.source_text(source_text)
+                        ));
+                    }
+                    for (name, value) in headers {
+                        statements.push(format!(
+                            "{}.headers.set({}, {});",
+                            response,
+                            serde_json::Value::String(name),
+                            value.source_text(source_text)
+                        ));
+                    }
+                    statements.push(format!("return {};", value));
+                    if call.braceless {
+                        Fix::insert(format!("{{ {} }}", statements.join(" ")), return_statement)
+                    } else {
+                        Fix::insert(
+                            statements.join(&format!("\n{}", indentation)),
+                            return_statement,
+                        )
+                    }
+                }
+                _ => {
+                    let module = helpers[call.helper].module;
+                    let module = if rewrite_imports {
+                        DATA_MODULE
+                    } else if RUNTIME_MODULES.contains(&module) {
+                        module
+                    } else {
+                        DEFAULT_DATA_MODULE
+                    };
+                    if !data_modules.contains(&module) {
+                        data_modules.push(module);
+                    }
+                    Fix::insert(data_name, call.callee)
+                }
+            };
+            fixes.push(fix.with_rule(
+                "unwrap-response-helper",
+                format!(
+                    "Return plain data instead of `{}()`",
+                    helpers[call.helper].local
+                ),
+            ));
+        }
+    }
+
+    // Drop helper imports that are no longer used
+    let unused = helpers
+        .iter()
+        .zip(unwrapped.iter())
+        .filter(|(helper, unwrapped)| {
+            **unwrapped > 0 && count_references(semantic, helper.local) == **unwrapped
+        })
+        .map(|(helper, _)| helper)
+        .collect::<Vec<_>>();
+
+    let mut removed = unused.iter().map(|h| h.local).collect::<Vec<_>>();
+    for module in data_modules {
+        let imported = if module == DATA_MODULE {
+            "data"
+        } else {
+            "unstable_data"
+        };
+        let data_specifier = if imported == data_name {
+            imported.to_owned()
+        } else {
+            format!("{} as {}", imported, data_name)
+        };
+        // Reuse a removed import from the same module, so the declaration stays
+        let reused = unused.iter().find(|h| h.module == module).and_then(|h| {
+            replace_named_import(program, source_text, module, h.local, &[&data_specifier])
+                .map(|fixes| (h.local, fixes))
+        });
+        match reused {
+            Some((local, replace_fixes)) => {
+                removed.retain(|name| *name != local);
+                fixes.extend(replace_fixes);
+            }
+            None => fixes.extend(add_named_imports(
+                program,
+                source_text,
+                module,
+                &[&data_specifier],
+                false,
+            )),
+        }
+    }
+    fixes.extend(remove_named_imports(program, source_text, &removed));
+
+    fixes
+}
+
+fn get_response_helpers<'a>(
+    program: &Program<'a>,
+    data_hooks: &DataHooks,
+) -> Vec<ResponseHelper<'a>> {
+    let mut helpers = vec![];
+    for decl in get_import_declarations(program) {
+        let module = decl.source.value.as_str();
+        for specifier in decl.specifiers.iter().flatten() {
+            if let ImportDeclarationSpecifier::ImportSpecifier(specifier) = specifier {
+                let imported = specifier.imported.name();
+                if data_hooks
+                    .response_helpers
+                    .iter()
+                    .any(|h| h.module == module && h.helper == imported.as_str())
+                {
+                    helpers.push(ResponseHelper {
+                        local: specifier.local.name.as_str(),
+                        module,
+                    });
+                }
+            }
+        }
+    }
+    helpers
+}
+
+/// Params and body of a loader function, and the body expression of arrow
+/// functions with implicit returns
+type LoaderFunction<'b, 'a> = (
+    &'b FormalParameters<'a>,
+    &'b FunctionBody<'a>,
+    Option<&'b Expression<'a>>,
+);
+
+/// `loader` and `action` exports, including `export { loader }` specifiers,
+/// or `serverLoader` and `serverAction` in an existing defineRoute object
+fn get_loader_functions<'b, 'a>(program: &'b Program<'a>) -> Vec<LoaderFunction<'b, 'a>> {
+    let mut functions = vec![];
+
+    for statement in program.body.iter() {
+        match statement {
+            Statement::ExportNamedDeclaration(named_export) => match &named_export.declaration {
+                Some(Declaration::FunctionDeclaration(decl))
+                    if decl
+                        .id
+                        .as_ref()
+                        .is_some_and(|id| id.name == "loader" || id.name == "action") =>
+                {
+                    if let Some(body) = &decl.body {
+                        functions.push((&*decl.params, &**body, None));
+                    }
+                }
+                Some(Declaration::VariableDeclaration(decl)) => {
+                    for declarator in decl.declarations.iter() {
+                        let is_loader = matches!(
+                            &declarator.id.kind,
+                            BindingPatternKind::BindingIdentifier(ident)
+                                if ident.name == "loader" || ident.name == "action"
+                        );
+                        if let (true, Some(init)) = (is_loader, &declarator.init) {
+                            functions.extend(get_loader_function(init));
+                        }
+                    }
+                }
+                // `export { loader }`
+                None if named_export.source.is_none() => {
+                    for (name, local, _) in get_export_specifiers(named_export) {
+                        if name != "loader" && name != "action" {
+                            continue;
+                        }
+                        match find_declaration(program, local) {
+                            Some((_, Declaration::FunctionDeclaration(decl))) => {
+                                if let Some(body) = &decl.body {
+                                    functions.push((&*decl.params, &**body, None));
+                                }
+                            }
+                            Some((_, Declaration::VariableDeclaration(decl))) => {
+                                functions.extend(
+                                    decl.declarations
+                                        .iter()
+                                        .find(|d| {
+                                            d.id.get_identifier().is_some_and(|id| id == local)
+                                        })
+                                        .and_then(|d| d.init.as_ref())
+                                        .and_then(get_loader_function),
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Statement::ExportDefaultDeclaration(default_export) => {
+                let Some(object) = get_define_route_object(default_export, &["defineRoute"]) else {
+                    continue;
+                };
+                for property in object.properties.iter() {
+                    if let ObjectPropertyKind::ObjectProperty(property) = property {
+                        if property.key.is_specific_static_name("serverLoader")
+                            || property.key.is_specific_static_name("serverAction")
+                        {
+                            functions.extend(get_loader_function(&property.value));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    functions
+}
+
+fn get_loader_function<'b, 'a>(expression: &'b Expression<'a>) -> Option<LoaderFunction<'b, 'a>> {
+    match expression {
+        Expression::FunctionExpression(func) => func
+            .body
+            .as_ref()
+            .map(|body| (&*func.params, &**body, None)),
+        Expression::ArrowFunctionExpression(arrow_func) => Some((
+            &*arrow_func.params,
+            &*arrow_func.body,
+            arrow_func.get_expression(),
+        )),
+        _ => None,
+    }
+}
+
+/// Name of the single-fetch `response` stub, if the args destructure it
+fn get_response_binding<'a>(params: &FormalParameters<'a>) -> Option<&'a str> {
+    let Some(BindingPatternKind::ObjectPattern(pattern)) =
+        params.items.first().map(|p| &p.pattern.kind)
+    else {
+        return None;
+    };
+    pattern.properties.iter().find_map(|p| {
+        if !p.key.is_specific_static_name("response") {
+            return None;
+        }
+        match &p.value.kind {
+            BindingPatternKind::BindingIdentifier(ident) => Some(ident.name.as_str()),
+            _ => None,
+        }
+    })
+}
+
+fn get_response_call(
+    call_expr: &CallExpression,
+    helpers: &[ResponseHelper],
+    return_statement: Option<Span>,
+    braceless: bool,
+) -> Option<ResponseCall> {
+    let Expression::Identifier(callee) = &call_expr.callee else {
+        return None;
+    };
+    let helper = helpers.iter().position(|h| h.local == callee.name)?;
+
+    let mut arguments = call_expr.arguments.iter();
+    let value = arguments.next()?.as_expression()?;
+    let init = match arguments.next() {
+        None => None,
+        Some(argument) => Some(get_response_init(argument.as_expression()?)),
+    };
+    if arguments.next().is_some() {
+        return None;
+    }
+
+    Some(ResponseCall {
+        helper,
+        span: call_expr.span,
+        callee: callee.span,
+        value: value.span(),
+        value_is_object: matches!(value, Expression::ObjectExpression(_)),
+        init,
+        return_statement,
+        braceless,
+    })
+}
+
+fn get_response_init(init: &Expression) -> ResponseInit {
+    let Expression::ObjectExpression(object) = init else {
+        return match init {
+            Expression::NumericLiteral(literal) => ResponseInit::Mutation {
+                status: Some(literal.span),
+                headers: vec![],
+            },
+            _ => ResponseInit::Other,
+        };
+    };
+
+    let mut status = None;
+    let mut headers = vec![];
+    for property in object.properties.iter() {
+        let ObjectPropertyKind::ObjectProperty(property) = property else {
+            return ResponseInit::Other;
+        };
+        if property.key.is_specific_static_name("status") && !property.computed {
+            status = Some(property.value.span());
+        } else if property.key.is_specific_static_name("headers") && !property.computed {
+            let Expression::ObjectExpression(headers_object) = &property.value else {
+                return ResponseInit::Other;
+            };
+            for header in headers_object.properties.iter() {
+                match header {
+                    ObjectPropertyKind::ObjectProperty(header) if !header.computed => {
+                        let Some(name) = header.key.static_name() else {
+                            return ResponseInit::Other;
+                        };
+                        headers.push((name.to_string(), header.value.span()));
+                    }
+                    _ => return ResponseInit::Other,
+                }
+            }
+        } else {
+            return ResponseInit::Other;
+        }
+    }
+
+    ResponseInit::Mutation { status, headers }
+}
+
+fn count_references(semantic: &Semantic, name: &str) -> usize {
+    semantic
+        .nodes()
+        .iter()
+        .filter(
+            |node| matches!(node.kind(), AstKind::IdentifierReference(ident) if ident.name == name),
+        )
+        .count()
+}
+
+/// Helper calls returned from a function body, skipping nested functions
+struct ReturnCollector<'h, 'a> {
+    helpers: &'h [ResponseHelper<'a>],
+    calls: Vec<ResponseCall>,
+}
+
+impl<'h, 'a> ReturnCollector<'h, 'a> {
+    fn collect_return(&mut self, stmt: &ReturnStatement<'a>, braceless: bool) {
+        if let Some(Expression::CallExpression(call_expr)) = &stmt.argument {
+            if let Some(call) =
+                get_response_call(call_expr, self.helpers, Some(stmt.span), braceless)
+            {
+                self.calls.push(call);
+                return;
+            }
+        }
+        walk::walk_return_statement(self, stmt);
+    }
+}
+
+impl<'h, 'a> Visit<'a> for ReturnCollector<'h, 'a> {
+    /// Returns in blocks, function bodies and switch cases
+    fn visit_statements(&mut self, stmts: &oxc_allocator::Vec<'a, Statement<'a>>) {
+        for stmt in stmts {
+            match stmt {
+                Statement::ReturnStatement(return_statement) => {
+                    self.collect_return(return_statement, false)
+                }
+                _ => self.visit_statement(stmt),
+            }
+        }
+    }
+
+    fn visit_return_statement(&mut self, stmt: &ReturnStatement<'a>) {
+        self.collect_return(stmt, true);
+    }
+
+    fn visit_function(&mut self, _func: &Function<'a>, _flags: Option<ScopeFlags>) {}
+
+    fn visit_arrow_expression(&mut self, _expr: &ArrowFunctionExpression<'a>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{codemod_models::CodemodOptions, test_utils::assert_codemod_snapshot};
+
+    #[test]
+    fn test_unwrap_json() {
+        let input = r#"
+            import { json, redirect } from "@remix-run/node";
+
+            export async function loader({ params }) {
+              if (!params.userId) {
+                throw redirect("/");
+              }
+              const user = await getUser(params.userId);
+              return json({ user });
+            }
+
+            export const action = () => json({ ok: true });
+
+            export default function Component() {
+              const { user } = useLoaderData<typeof loader>();
+              return <h1>{user.name}</h1>;
+            }
+        "#;
+        assert_codemod_snapshot("unwrap_json", input, &CodemodOptions::default());
+    }
+
+    #[test]
+    fn test_unwrap_json_with_init() {
+        let input = r#"
+            import { json, defer } from "@remix-run/node";
+
+            export async function loader({ request }) {
+              const data = await getData(request);
+              if (!data) {
+                return json({ error: "Not found" }, { status: 404 });
+              }
+              return defer(data, { headers: new Headers({ "Cache-Control": "no-store" }) });
+            }
+
+            export function action({ response }) {
+              return json({ ok: true }, { status: 201, headers: { "X-Created": "yes" } });
+            }
+
+            export default function Component() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.name}</h1>;
+            }
+        "#;
+        assert_codemod_snapshot("unwrap_json_with_init", input, &CodemodOptions::default());
+    }
+
+    #[test]
+    fn test_unwrap_json_with_init_rewrite_imports() {
+        let input = r#"
+            import { json, redirect } from "@remix-run/node";
+
+            export async function loader({ params }) {
+              if (!params.userId) throw redirect("/");
+              return json({ userId: params.userId }, { status: 201 });
+            }
+
+            export default function Component() {
+              return null;
+            }
+        "#;
+        let options = CodemodOptions {
+            rewrite_imports: true,
+            ..Default::default()
+        };
+        assert_codemod_snapshot("unwrap_json_with_init_rewrite_imports", input, &options);
+    }
+
+    #[test]
+    fn test_unwrap_json_braceless_if() {
+        let input = r#"
+            import { json } from "@remix-run/node";
+
+            export async function loader({ params, response }) {
+              const data = await getData(params.id);
+              if (!data) return json({ error: "Not found" }, { status: 404 });
+              return json(data);
+            }
+
+            export default function Component() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.name}</h1>;
+            }
+        "#;
+        assert_codemod_snapshot(
+            "unwrap_json_braceless_if",
+            input,
+            &CodemodOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_unwrap_typedjson() {
+        let input = r#"
+            import { typedjson } from "remix-typedjson";
+            import { json } from "@remix-run/node";
+
+            export const loader = async () => {
+              const users = await getUsers();
+              return typedjson({ users, createdAt: new Date() });
+            };
+
+            export function ErrorBoundary() {
+              return json({ message: "kept" });
+            }
+        "#;
+        assert_codemod_snapshot("unwrap_typedjson", input, &CodemodOptions::default());
+    }
+}
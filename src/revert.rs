@@ -0,0 +1,738 @@
+//! Reverse of the codemod: turn `export default defineRoute({ ... })` back
+//! into classic Remix route module exports.
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    BindingPatternKind, Expression, FormalParameters, FunctionBody, ObjectProperty,
+    ObjectPropertyKind, Statement,
+};
+use oxc_parser::Parser;
+use oxc_semantic::{Semantic, SemanticBuilder};
+use oxc_span::{GetSpan, SourceType, Span};
+
+use std::process;
+
+use crate::{
+    codemod::{
+        apply_fixes, get_args_span, get_define_route_object, get_indentation, run_transform,
+    },
+    codemod_models::{CodemodError, CodemodOptions, CodemodOutput, ResourceRouteForm},
+    fixer::Fix,
+    imports::{add_named_imports, remove_named_import, replace_named_import},
+    sourcemap::{ByteMap, MappedText},
+};
+
+pub fn revert(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
+    run_transform(source_text, source_type, options, transform)
+}
+
+fn transform(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+
+    if !ret.errors.is_empty() {
+        for error in ret.errors {
+            let error = error.with_source_code(source_text.to_string());
+            println!("{error:?}");
+        }
+        process::exit(1);
+    }
+
+    let semantic_ret = SemanticBuilder::new(source_text, source_type)
+        .with_trivias(ret.trivias)
+        .build(&ret.program);
+
+    let Some((statement_span, object)) = ret.program.body.iter().find_map(|s| match s {
+        Statement::ExportDefaultDeclaration(decl) => {
+            get_define_route_object(decl, &options.define_route_helpers())
+                .map(|object| (decl.span, object))
+        }
+        _ => None,
+    }) else {
+        return Ok(CodemodOutput {
+            code: source_text.to_string(),
+            source_map: options
+                .source_map
+                .then(|| ByteMap::identity(source_text.len() as u32)),
+            diagnostics: vec![],
+            changed: false,
+        });
+    };
+
+    let mut properties = vec![];
+    for property in object.properties.iter() {
+        match property {
+            // `defineRoute({ resource: true, ... })`
+            ObjectPropertyKind::ObjectProperty(p)
+                if options.resource_route == Some(ResourceRouteForm::Flag)
+                    && p.key.is_specific_static_name("resource") => {}
+            ObjectPropertyKind::ObjectProperty(p) if p.key.static_name().is_some() => {
+                properties.push(&**p);
+            }
+            _ => {
+                return Err(CodemodError::UnsupportedRouteProperty(
+                    property.span().source_text(source_text).to_owned(),
+                ))
+            }
+        }
+    }
+
+    let keys = properties
+        .iter()
+        .map(|p| p.key.static_name().unwrap().to_string())
+        .collect::<Vec<_>>();
+    let has_key = |key: &str| keys.iter().any(|k| k == key);
+
+    let context = RevertContext {
+        source_text,
+        semantic: &semantic_ret.semantic,
+        is_typescript: source_type.is_typescript(),
+        loader_name: if has_key("serverLoader") {
+            Some("loader")
+        } else if has_key("clientLoader") {
+            Some("clientLoader")
+        } else {
+            None
+        },
+        action_name: if has_key("serverAction") {
+            Some("action")
+        } else if has_key("clientAction") {
+            Some("clientAction")
+        } else {
+            None
+        },
+    };
+
+    let mut exports = vec![];
+    let mut hooks = vec![];
+    let mut types = vec![];
+    let mut client_loader_hydrate = None;
+
+    for (property, key) in properties.iter().zip(keys.iter()) {
+        match key.as_str() {
+            "clientLoaderHydrate" => {
+                let mut statement = MappedText::default();
+                statement.push_str("clientLoader.hydrate = ");
+                statement.push_source(
+                    property.value.span().source_text(source_text),
+                    property.value.span().start,
+                );
+                statement.push_str(";");
+                client_loader_hydrate = Some(statement);
+            }
+            "Component" | "Layout" => {
+                let (export, component_hooks) = revert_component(property, key, &context)?;
+                exports.push((key.as_str(), export));
+                hooks.extend(component_hooks);
+            }
+            _ => {
+                let name = revert_rename(key);
+                let (export, type_import) = revert_export(property, name, &context);
+                exports.push((name, export));
+                types.extend(type_import);
+            }
+        }
+    }
+
+    if let Some(statement) = client_loader_hydrate {
+        let position = exports
+            .iter()
+            .position(|(name, _)| *name == "clientLoader")
+            .map_or(exports.len(), |i| i + 1);
+        exports.insert(position, ("clientLoader.hydrate", statement));
+    }
+
+    // Drop the blank lines the codemod leaves behind the removed exports
+    let start = source_text[..statement_span.start as usize]
+        .trim_end()
+        .len() as u32;
+    let mut content = MappedText::default();
+    if start > 0 {
+        content.push_str("\n\n");
+    }
+    for (i, (_, export)) in exports.iter().enumerate() {
+        if i > 0 {
+            content.push_str("\n\n");
+        }
+        content.push_mapped(export);
+    }
+
+    let mut code_fixes = vec![
+        Fix::insert_mapped(content, Span::new(start, statement_span.end)).with_rule(
+            "revert-define-route",
+            "Replace defineRoute with route exports",
+        ),
+    ];
+
+    hooks.sort();
+    hooks.dedup();
+    // Reuse the defineRoute import for the hooks when possible
+    code_fixes.extend(
+        replace_named_import(
+            &ret.program,
+            source_text,
+            "@remix-run/react",
+            "defineRoute",
+            &hooks,
+        )
+        .unwrap_or_else(|| {
+            let mut fixes =
+                add_named_imports(&ret.program, source_text, "@remix-run/react", &hooks, false);
+            fixes.extend(remove_named_import(
+                &ret.program,
+                source_text,
+                "defineRoute",
+            ));
+            fixes
+        }),
+    );
+    for module in ["@remix-run/node", "@remix-run/react"] {
+        let mut names = types
+            .iter()
+            .filter(|(m, _)| *m == module)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        code_fixes.extend(add_named_imports(
+            &ret.program,
+            source_text,
+            module,
+            &names,
+            true,
+        ));
+    }
+
+    let (code, source_map) = apply_fixes(source_text, code_fixes, options.source_map)?;
+
+    Ok(CodemodOutput {
+        changed: code != source_text,
+        code,
+        source_map,
+        diagnostics: vec![],
+    })
+}
+
+struct RevertContext<'s, 'a> {
+    source_text: &'s str,
+    semantic: &'s Semantic<'a>,
+    is_typescript: bool,
+    /// Export that `loaderData` comes from
+    loader_name: Option<&'static str>,
+    /// Export that `actionData` comes from
+    action_name: Option<&'static str>,
+}
+
+/// Inverse of `rename_exports`
+fn revert_rename(key: &str) -> &str {
+    match key {
+        "serverLoader" => "loader",
+        "serverAction" => "action",
+        _ => key,
+    }
+}
+
+/// Type of the args of each route export, and the module it comes from
+fn get_args_type(name: &str) -> Option<(&'static str, &'static str)> {
+    match name {
+        "loader" => Some(("@remix-run/node", "LoaderFunctionArgs")),
+        "action" => Some(("@remix-run/node", "ActionFunctionArgs")),
+        "headers" => Some(("@remix-run/node", "HeadersArgs")),
+        "meta" => Some(("@remix-run/node", "MetaArgs")),
+        "clientLoader" => Some(("@remix-run/react", "ClientLoaderFunctionArgs")),
+        "clientAction" => Some(("@remix-run/react", "ClientActionFunctionArgs")),
+        "shouldRevalidate" => Some(("@remix-run/react", "ShouldRevalidateFunctionArgs")),
+        _ => None,
+    }
+}
+
+struct FunctionParts<'b, 'a> {
+    is_async: bool,
+    is_arrow: bool,
+    params: &'b FormalParameters<'a>,
+    body: &'b FunctionBody<'a>,
+    /// Arrow functions with implicit returns, like `() => stuff`
+    expression: Option<&'b Expression<'a>>,
+}
+
+fn get_function_parts<'b, 'a>(value: &'b Expression<'a>) -> Option<FunctionParts<'b, 'a>> {
+    match value {
+        Expression::FunctionExpression(func) => func.body.as_ref().map(|body| FunctionParts {
+            is_async: func.r#async,
+            is_arrow: false,
+            params: &func.params,
+            body,
+            expression: None,
+        }),
+        Expression::ArrowFunctionExpression(arrow_func) => Some(FunctionParts {
+            is_async: arrow_func.r#async,
+            is_arrow: true,
+            params: &arrow_func.params,
+            body: &arrow_func.body,
+            expression: arrow_func.get_expression(),
+        }),
+        _ => None,
+    }
+}
+
+/// `export { loader }` for a value that refers to a binding of the module, like
+/// the ones the codemod keeps when they're used elsewhere. `export const
+/// loader = loader` would declare it twice.
+fn revert_local_binding(
+    value: &Expression,
+    name: &str,
+    context: &RevertContext,
+) -> Option<MappedText> {
+    let Expression::Identifier(ident) = value else {
+        return None;
+    };
+    context.semantic.scopes().get_root_binding(&ident.name)?;
+
+    let mut export = MappedText::default();
+    export.push_str("export { ");
+    export.push_source(&ident.name, ident.span.start);
+    if ident.name != name {
+        export.push_str(&format!(" as {}", name));
+    }
+    export.push_str(" };");
+    Some(export)
+}
+
+/// Span of the params without the parentheses, arrow functions can omit them
+fn get_params_inner_span(params: &FormalParameters, source_text: &str) -> Span {
+    if source_text[params.span.start as usize..].starts_with('(') {
+        get_args_span(params.span)
+    } else {
+        params.span
+    }
+}
+
+fn revert_export(
+    property: &ObjectProperty,
+    name: &str,
+    context: &RevertContext,
+) -> (MappedText, Option<(&'static str, &'static str)>) {
+    let source_text = context.source_text;
+    let indentation = get_indentation(source_text, property.span.start);
+    let mut export = MappedText::default();
+
+    if let Some(export) = revert_local_binding(&property.value, name, context) {
+        return (export, None);
+    }
+
+    let Some(function) = get_function_parts(&property.value) else {
+        export.push_str(&format!("export const {} = ", name));
+        let span = property.value.span();
+        export.push_source(span.source_text(source_text), span.start);
+        export.push_str(";");
+        return (export.dedent_by(indentation), None);
+    };
+
+    if function.is_arrow {
+        export.push_str(&format!(
+            "export const {} = {}(",
+            name,
+            if function.is_async { "async " } else { "" }
+        ));
+    } else {
+        export.push_str(&format!(
+            "export {}function {}(",
+            if function.is_async { "async " } else { "" },
+            name
+        ));
+    }
+
+    let params = get_params_inner_span(function.params, source_text);
+    export.push_source(params.source_text(source_text), params.start);
+
+    // Restore the args type annotation the codemod has stripped
+    let mut type_import = None;
+    if let (true, Some(first), Some((module, type_name))) = (
+        context.is_typescript,
+        function.params.items.first(),
+        get_args_type(name),
+    ) {
+        if first.pattern.type_annotation.is_none() && function.params.items.len() == 1 {
+            export.push_str(&format!(": {}", type_name));
+            type_import = Some((module, type_name));
+        }
+    }
+
+    export.push_str(if function.is_arrow { ") => " } else { ") " });
+    let body = match function.expression {
+        Some(expression) => expression.span(),
+        None => function.body.span,
+    };
+    export.push_source(body.source_text(source_text), body.start);
+    if function.is_arrow {
+        export.push_str(";");
+    }
+
+    (export.dedent_by(indentation), type_import)
+}
+
+fn revert_component(
+    property: &ObjectProperty,
+    key: &str,
+    context: &RevertContext,
+) -> Result<(MappedText, Vec<&'static str>), CodemodError> {
+    let source_text = context.source_text;
+    let indentation = get_indentation(source_text, property.span.start);
+    let mut export = MappedText::default();
+    let is_layout = key == "Layout";
+
+    if is_layout {
+        if let Some(export) = revert_local_binding(&property.value, key, context) {
+            return Ok((export, vec![]));
+        }
+    }
+
+    let Some(function) = get_function_parts(&property.value) else {
+        export.push_str(if is_layout {
+            "export const Layout = "
+        } else {
+            "export default "
+        });
+        let span = property.value.span();
+        export.push_source(span.source_text(source_text), span.start);
+        export.push_str(";");
+        return Ok((export.dedent_by(indentation), vec![]));
+    };
+
+    let mut hook_statements = vec![];
+    let mut hooks = vec![];
+    let mut params = MappedText::default();
+
+    let mut add_hook = |binding: &str, key: &str| -> String {
+        let (hook, export_name) = if key == "actionData" {
+            ("useActionData", context.action_name)
+        } else {
+            ("useLoaderData", context.loader_name)
+        };
+        hooks.push(hook);
+        match (context.is_typescript, export_name) {
+            (true, Some(export_name)) => {
+                format!("const {} = {}<typeof {}>();", binding, hook, export_name)
+            }
+            _ => format!("const {} = {}();", binding, hook),
+        }
+    };
+
+    match function.params.items.first().map(|p| &p.pattern.kind) {
+        None => {}
+        Some(BindingPatternKind::ObjectPattern(pattern)) => {
+            let mut kept = vec![];
+            for p in pattern.properties.iter() {
+                match p.key.static_name() {
+                    Some(key) if key == "loaderData" || key == "actionData" => {
+                        let binding = match &p.value.kind {
+                            BindingPatternKind::AssignmentPattern(assignment) => {
+                                assignment.left.span()
+                            }
+                            _ => p.value.span(),
+                        };
+                        hook_statements.push(add_hook(binding.source_text(source_text), &key));
+                    }
+                    _ => kept.push(p.span),
+                }
+            }
+            if let Some(rest) = &pattern.rest {
+                kept.push(rest.span);
+            }
+            if !kept.is_empty() {
+                params.push_str("{ ");
+                for (i, span) in kept.iter().enumerate() {
+                    if i > 0 {
+                        params.push_str(", ");
+                    }
+                    params.push_source(span.source_text(source_text), span.start);
+                }
+                params.push_str(" }");
+            }
+        }
+        // `Layout(props)` also needs `props.children`
+        Some(BindingPatternKind::BindingIdentifier(ident)) if is_layout => {
+            params.push_source(&ident.name, ident.span.start);
+        }
+        Some(BindingPatternKind::BindingIdentifier(ident)) => {
+            let mut values = vec![];
+            for (key, export_name) in [
+                ("loaderData", context.loader_name),
+                ("actionData", context.action_name),
+            ] {
+                if export_name.is_some() {
+                    let statement = add_hook("$", key);
+                    let call = statement
+                        .trim_start_matches("const $ = ")
+                        .trim_end_matches(';')
+                        .to_owned();
+                    values.push(format!("{}: {}", key, call));
+                }
+            }
+            hook_statements.push(format!(
+                "const {} = {{ {} }};",
+                ident.name,
+                values.join(", ")
+            ));
+        }
+        Some(_) => {
+            return Err(CodemodError::UnsupportedComponentArgs(
+                get_params_inner_span(function.params, source_text)
+                    .source_text(source_text)
+                    .to_owned(),
+            ))
+        }
+    }
+
+    export.push_str(if is_layout {
+        "export function Layout("
+    } else {
+        "export default function Component("
+    });
+    export.push_mapped(&params);
+    export.push_str(") ");
+
+    let hook_lines = hook_statements
+        .iter()
+        .map(|statement| format!("\n{}{}", " ".repeat(indentation + 2), statement))
+        .collect::<String>();
+
+    match function.expression {
+        Some(expression) => {
+            let span = expression.span();
+            export.push_str(&format!(
+                "{{{}\n{}return ",
+                hook_lines,
+                " ".repeat(indentation + 2)
+            ));
+            export.push_source(span.source_text(source_text), span.start);
+            export.push_str(&format!(";\n{}}}", " ".repeat(indentation)));
+        }
+        None => {
+            let body = function.body.span;
+            export.push_source("{", body.start);
+            export.push_str(&hook_lines);
+            export.push_source(
+                Span::new(body.start + 1, body.end).source_text(source_text),
+                body.start + 1,
+            );
+        }
+    }
+
+    Ok((export.dedent_by(indentation), hooks))
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::SourceType;
+
+    use super::revert;
+    use crate::{
+        codemod::codemod,
+        codemod_models::{CodemodOptions, ResourceRouteForm},
+        test_utils::{assert_output_snapshot, assert_transform_snapshot, outdent},
+    };
+
+    #[test]
+    fn test_revert_kitchen_sink() {
+        let input = r#"
+            import { defineRoute } from "@remix-run/react";
+
+            const title = "User page";
+
+            export default defineRoute({
+              async serverLoader({ params }) {
+                const { userId } = params;
+                return { userId };
+              },
+              serverAction: async ({ request }) => {
+                return { ok: true };
+              },
+              async clientLoader({ serverLoader }) {
+                return await serverLoader();
+              },
+              Component({ loaderData: data, actionData }) {
+                return <h1>User: {data.userId} {actionData?.ok}</h1>;
+              },
+              meta: () => [{ title }],
+              links: () => [{ rel: "icon", href: "/favicon.png" }],
+              clientLoaderHydrate: true,
+            });
+        "#;
+        assert_snapshot("revert_kitchen_sink", input);
+    }
+
+    #[test]
+    fn test_revert_component_arrow_function_expression() {
+        let input = r#"
+            import { Link } from "@remix-run/react";
+
+            export default defineRoute({
+              serverLoader: () => ({ hello: "world" }),
+              Component: ({ loaderData: { hello } }) => <Link to="/">{hello}</Link>,
+            });
+        "#;
+        assert_snapshot("revert_component_arrow_function_expression", input);
+    }
+
+    #[test]
+    fn test_revert_component_props() {
+        let input = r#"
+            export default defineRoute({
+              serverLoader() {
+                return { hello: "world" };
+              },
+              Component(props) {
+                return <h1>{props.loaderData.hello}</h1>;
+              },
+            });
+        "#;
+        assert_snapshot("revert_component_props", input);
+    }
+
+    #[test]
+    fn test_revert_root_layout() {
+        let input = r#"
+            export default defineRoute({
+              serverLoader() {
+                return { lang: "en" };
+              },
+              Layout({ children, loaderData: data }) {
+                return <html lang={data?.lang}><body>{children}</body></html>;
+              },
+              Component() {
+                return <Outlet />;
+              },
+            });
+        "#;
+        assert_snapshot("revert_root_layout", input);
+    }
+
+    #[test]
+    fn test_revert_local_bindings() {
+        let input = r#"
+            import { defineRoute } from "@remix-run/react";
+            import { getUser } from "~/models/user";
+
+            const meta = () => [{ title: "User" }];
+            const Layout = ({ children }) => <main>{children}</main>;
+
+            export default defineRoute({
+              serverLoader: getUser,
+              meta,
+              links: () => meta().map(() => ({ rel: "icon", href: "/favicon.png" })),
+              Layout: Layout,
+              Component() {
+                return null;
+              },
+            });
+        "#;
+        assert_snapshot("revert_local_bindings", input);
+    }
+
+    #[test]
+    fn test_revert_resource_route() {
+        let input = r#"
+            export default defineResourceRoute({
+              async serverLoader() {
+                return new Response("ok");
+              },
+            });
+        "#;
+        let options = CodemodOptions {
+            resource_route: Some(ResourceRouteForm::default()),
+            ..Default::default()
+        };
+        assert_transform_snapshot(
+            "revert_resource_route",
+            input,
+            "path/to/file.tsx",
+            &options,
+            revert,
+        );
+    }
+
+    #[test]
+    fn test_revert_javascript_route() {
+        let input = r#"
+            export default defineRoute({
+              async serverLoader({ params }) {
+                return { id: params.id };
+              },
+              Component({ loaderData }) {
+                return <h1>{loaderData.id}</h1>;
+              },
+            });
+        "#;
+        assert_snapshot_with_path("revert_javascript_route", input, "path/to/file.jsx");
+    }
+
+    #[test]
+    fn test_revert_round_trip() {
+        let input = outdent(
+            r#"
+            import type { LoaderFunctionArgs } from "@remix-run/node";
+            import { useLoaderData } from "@remix-run/react";
+
+            export async function loader({ params }: LoaderFunctionArgs) {
+              return { userId: params.userId };
+            }
+
+            export default function Component() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>User: {data.userId}</h1>;
+            }
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions::default();
+        let migrated = codemod(&input, source_type, &options).unwrap().code;
+        let reverted = revert(&migrated, source_type, &options).unwrap().code;
+        assert_eq!(reverted, input);
+    }
+
+    #[test]
+    fn test_revert_codemod_local_binding() {
+        let input = outdent(
+            r#"
+            import type { SerializeFrom } from "@remix-run/node";
+
+            export async function loader() {
+              return { hello: "world" };
+            }
+
+            export const meta = () => [{ title: "Hello" }];
+
+            export const links = () => meta().map(() => ({ rel: "icon", href: "/favicon.png" }));
+
+            type LoaderData = SerializeFrom<typeof loader>;
+
+            export default function Component() {
+              return null;
+            }
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions::default();
+        let migrated = codemod(&input, source_type, &options).unwrap().code;
+        assert!(migrated.contains("serverLoader: loader"));
+        let reverted = revert(&migrated, source_type, &options).unwrap().code;
+        assert_output_snapshot("revert_codemod_local_binding", &migrated, &reverted);
+    }
+
+    fn assert_snapshot(name: &str, input: &str) {
+        assert_snapshot_with_path(name, input, "path/to/file.tsx");
+    }
+
+    fn assert_snapshot_with_path(name: &str, input: &str, path: &str) {
+        assert_transform_snapshot(name, input, path, &CodemodOptions::default(), revert);
+    }
+}
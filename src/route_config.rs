@@ -0,0 +1,269 @@
+//! Generate a React Router `app/routes.ts` config from the Remix route tree
+
+use serde_json::Value;
+
+#[derive(Clone)]
+struct RouteEntry {
+    id: String,
+    path: Option<String>,
+    index: bool,
+    case_sensitive: bool,
+    file: String,
+    children: Vec<RouteEntry>,
+}
+
+impl RouteEntry {
+    fn from_json(value: &Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        Some(Self {
+            id: obj.get("id")?.as_str()?.to_owned(),
+            path: obj
+                .get("path")
+                .and_then(|p| p.as_str())
+                .filter(|p| !p.is_empty())
+                .map(|p| p.to_owned()),
+            index: obj.get("index").and_then(|i| i.as_bool()).unwrap_or(false),
+            case_sensitive: obj
+                .get("caseSensitive")
+                .and_then(|c| c.as_bool())
+                .unwrap_or(false),
+            file: obj.get("file")?.as_str()?.to_owned(),
+            children: obj
+                .get("children")
+                .and_then(|c| c.as_array())
+                .map(|c| c.iter().filter_map(RouteEntry::from_json).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Leading path segment shared with siblings, and the rest of the path
+    fn split_prefix(&self) -> Option<(&str, &str)> {
+        let path = self.path.as_deref()?;
+        match path.split_once('/') {
+            Some((first, rest)) if !first.is_empty() => Some((first, rest)),
+            // Index routes can't have a path of their own
+            None if self.index => Some((path, "")),
+            _ => None,
+        }
+    }
+
+    fn with_path(&self, path: &str) -> Self {
+        Self {
+            path: Some(path.to_owned()).filter(|p| !p.is_empty()),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Helpers {
+    index: bool,
+    layout: bool,
+    prefix: bool,
+    route: bool,
+}
+
+/// `app/routes.ts` module equivalent to the output of `remix routes --json`.
+/// The root route is implicit in React Router, so only its children are listed.
+pub fn generate_route_config(routes_json: &Value) -> String {
+    let entries = routes_json
+        .as_array()
+        .map(|routes| {
+            routes
+                .iter()
+                .filter_map(RouteEntry::from_json)
+                .flat_map(|entry| {
+                    if entry.id == "root" {
+                        entry.children
+                    } else {
+                        vec![entry]
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut helpers = Helpers::default();
+    let mut body = String::new();
+    render_entries(&entries, 1, &mut helpers, &mut body);
+
+    let mut imports = vec!["type RouteConfig"];
+    for (used, name) in [
+        (helpers.index, "index"),
+        (helpers.layout, "layout"),
+        (helpers.prefix, "prefix"),
+        (helpers.route, "route"),
+    ] {
+        if used {
+            imports.push(name);
+        }
+    }
+
+    format!(
+        "import {{ {} }} from \"@react-router/dev/routes\";\n\nexport default [\n{}] satisfies RouteConfig;\n",
+        imports.join(", "),
+        body
+    )
+}
+
+fn render_entries(
+    entries: &[RouteEntry],
+    depth: usize,
+    helpers: &mut Helpers,
+    output: &mut String,
+) {
+    let indentation = "  ".repeat(depth);
+    let mut rendered_prefixes = vec![];
+
+    for entry in entries.iter() {
+        let Some((prefix, _)) = entry.split_prefix() else {
+            render_entry(entry, depth, helpers, output);
+            continue;
+        };
+        if rendered_prefixes.contains(&prefix) {
+            continue;
+        }
+        rendered_prefixes.push(prefix);
+
+        // Group all siblings under the same leading segment into one `prefix()`
+        let group = entries
+            .iter()
+            .filter_map(|e| match e.split_prefix() {
+                Some((p, rest)) if p == prefix => Some(e.with_path(rest)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if group.len() == 1 && !entry.index {
+            render_entry(entry, depth, helpers, output);
+            continue;
+        }
+
+        helpers.prefix = true;
+        output.push_str(&format!("{}...prefix({}, [\n", indentation, quote(prefix)));
+        render_entries(&group, depth + 1, helpers, output);
+        output.push_str(&format!("{}]),\n", indentation));
+    }
+}
+
+fn render_entry(entry: &RouteEntry, depth: usize, helpers: &mut Helpers, output: &mut String) {
+    let indentation = "  ".repeat(depth);
+
+    let mut args = vec![];
+    let helper = if entry.index {
+        helpers.index = true;
+        "index"
+    } else if let Some(path) = &entry.path {
+        helpers.route = true;
+        args.push(quote(path));
+        "route"
+    } else {
+        helpers.layout = true;
+        "layout"
+    };
+    args.push(quote(&entry.file));
+
+    let mut options = vec![];
+    if entry.id != get_default_id(&entry.file) {
+        options.push(format!("id: {}", quote(&entry.id)));
+    }
+    if entry.case_sensitive {
+        options.push("caseSensitive: true".to_owned());
+    }
+    if !options.is_empty() {
+        args.push(format!("{{ {} }}", options.join(", ")));
+    }
+
+    if entry.children.is_empty() {
+        output.push_str(&format!(
+            "{}{}({}),\n",
+            indentation,
+            helper,
+            args.join(", ")
+        ));
+        return;
+    }
+
+    output.push_str(&format!(
+        "{}{}({}, [\n",
+        indentation,
+        helper,
+        args.join(", ")
+    ));
+    render_entries(&entry.children, depth + 1, helpers, output);
+    output.push_str(&format!("{}]),\n", indentation));
+}
+
+/// React Router derives route ids from the file path without its extension
+fn get_default_id(file: &str) -> &str {
+    match file.rfind('.') {
+        Some(dot) if !file[dot..].contains('/') => &file[..dot],
+        _ => file,
+    }
+}
+
+fn quote(text: &str) -> String {
+    Value::String(text.to_owned()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::generate_route_config;
+    use crate::test_utils::assert_output_snapshot;
+
+    #[test]
+    fn test_route_config_remix_app() {
+        // `remix routes --json` in ./remix-app
+        let routes = json!([{
+            "id": "root",
+            "path": "",
+            "file": "root.tsx",
+            "children": [
+                { "id": "routes/_index", "index": true, "file": "routes/_index.tsx" },
+                { "id": "routes/splat.$", "path": "splat/*", "file": "routes/splat.$.tsx" },
+                {
+                    "id": "routes/users.$userId",
+                    "path": "users/:userId",
+                    "file": "routes/users.$userId.tsx",
+                    "children": [
+                        {
+                            "id": "routes/users.$userId.$postId",
+                            "path": ":postId",
+                            "file": "routes/users.$userId.$postId.tsx"
+                        }
+                    ]
+                }
+            ]
+        }]);
+        assert_snapshot("route_config_remix_app", routes);
+    }
+
+    #[test]
+    fn test_route_config_layouts_and_prefixes() {
+        let routes = json!([{
+            "id": "root",
+            "path": "",
+            "file": "root.tsx",
+            "children": [
+                {
+                    "id": "routes/_auth",
+                    "file": "routes/_auth.tsx",
+                    "children": [
+                        { "id": "routes/_auth.login", "path": "login", "file": "routes/_auth.login.tsx" },
+                        { "id": "routes/_auth.signup", "path": "signup", "file": "routes/_auth.signup.tsx" }
+                    ]
+                },
+                { "id": "routes/settings._index", "path": "settings", "index": true, "file": "routes/settings._index.tsx" },
+                { "id": "routes/settings.profile", "path": "settings/profile", "file": "routes/settings.profile.tsx" },
+                { "id": "routes/settings.password", "path": "settings/password", "file": "routes/settings.password.tsx", "caseSensitive": true },
+                { "id": "marketing", "path": "about", "file": "routes/about/route.tsx" }
+            ]
+        }]);
+        assert_snapshot("route_config_layouts_and_prefixes", routes);
+    }
+
+    fn assert_snapshot(name: &str, routes: Value) {
+        assert_output_snapshot(name, &routes.to_string(), &generate_route_config(&routes));
+    }
+}
@@ -0,0 +1,778 @@
+//! Project-wide pass over modules that import route modules, like tests and
+//! stories doing `import { loader } from "~/routes/users"`. Exports that moved
+//! into defineRoute are read from the route's default export instead.
+
+use oxc_allocator::Allocator;
+use oxc_ast::{
+    ast::{
+        Expression, ImportDeclaration, ImportDeclarationSpecifier, ModuleExportName, Program,
+        Statement,
+    },
+    AstKind,
+};
+use oxc_parser::Parser;
+use oxc_semantic::{Semantic, SemanticBuilder, SymbolId};
+use oxc_span::{GetSpan, SourceType, Span};
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    codemod::{
+        apply_fixes, rename_exports, run_transform, KNOWN_REMIX_EXPORTS, ROOT_ROUTE_EXPORTS,
+    },
+    codemod_models::{CodemodError, CodemodOptions, CodemodOutput, Diagnostic, ManifestRoute},
+    fixer::Fix,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteImportMode {
+    /// Read moved exports from the route's default export
+    Rewrite,
+    /// Only report the imports that need to change
+    Report,
+}
+
+/// Route file an import source refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteModule {
+    Route,
+    /// Its `Layout` moved into defineRoute as well
+    Root,
+}
+
+const EXTENSIONS: [&str; 4] = ["tsx", "ts", "jsx", "js"];
+
+/// Rewrite imports of moved route exports in a module. `get_route_module`
+/// tells which route file, if any, an import source refers to.
+pub fn rewrite_route_imports(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+    mode: RouteImportMode,
+    get_route_module: &dyn Fn(&str) -> Option<RouteModule>,
+) -> Result<CodemodOutput, CodemodError> {
+    run_transform(
+        source_text,
+        source_type,
+        options,
+        |source_text, source_type, options| {
+            transform(source_text, source_type, options, mode, get_route_module)
+        },
+    )
+}
+
+fn transform(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+    mode: RouteImportMode,
+    get_route_module: &dyn Fn(&str) -> Option<RouteModule>,
+) -> Result<CodemodOutput, CodemodError> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+
+    // Any file in the project ends up here, so don't give up on the whole run
+    if !ret.errors.is_empty() {
+        return Ok(CodemodOutput {
+            code: source_text.to_string(),
+            source_map: None,
+            diagnostics: vec![Diagnostic::new(
+                "route-import",
+                "File doesn't parse, imports of route modules weren't checked".to_owned(),
+                Span::new(0, 0),
+            )],
+            changed: false,
+        });
+    }
+
+    let semantic_ret = SemanticBuilder::new(source_text, source_type)
+        .with_trivias(ret.trivias)
+        .build(&ret.program);
+    let semantic = semantic_ret.semantic;
+
+    let mut fixes = vec![];
+    let mut diagnostics = vec![];
+    let mut taken_names = vec![];
+
+    for statement in ret.program.body.iter() {
+        match statement {
+            Statement::ImportDeclaration(decl) => {
+                let Some(module) = get_route_module(&decl.source.value) else {
+                    continue;
+                };
+                let import = RouteImport {
+                    decl,
+                    module,
+                    program: &ret.program,
+                    semantic: &semantic,
+                    source_text,
+                };
+                match mode {
+                    RouteImportMode::Rewrite => {
+                        let (import_fixes, import_diagnostics) = import.rewrite(&mut taken_names);
+                        fixes.extend(import_fixes);
+                        diagnostics.extend(import_diagnostics);
+                    }
+                    RouteImportMode::Report => diagnostics.extend(import.report()),
+                }
+            }
+            Statement::ExportNamedDeclaration(decl) => {
+                let Some((source, module)) = decl
+                    .source
+                    .as_ref()
+                    .and_then(|s| get_route_module(&s.value).map(|module| (s, module)))
+                else {
+                    continue;
+                };
+                for specifier in decl.specifiers.iter() {
+                    if let Some(key) = get_route_property(specifier.local.name(), module) {
+                        diagnostics.push(Diagnostic::new(
+                            "route-import",
+                            format!(
+                                "`{}` is re-exported from \"{}\", it's now `{}` on its default export",
+                                specifier.local.name(),
+                                source.value,
+                                key
+                            ),
+                            specifier.span,
+                        ));
+                    }
+                }
+            }
+            Statement::ExportAllDeclaration(decl)
+                if get_route_module(&decl.source.value).is_some() =>
+            {
+                diagnostics.push(Diagnostic::new(
+                    "route-import",
+                    format!(
+                        "Route exports re-exported from \"{}\" are now on its default export",
+                        decl.source.value
+                    ),
+                    decl.span,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    // `import("~/routes/users")`
+    for node in semantic.nodes().iter() {
+        if let AstKind::ImportExpression(import_expr) = node.kind() {
+            if let Expression::StringLiteral(source) = &import_expr.source {
+                if get_route_module(&source.value).is_some() {
+                    diagnostics.push(Diagnostic::new(
+                        "route-import",
+                        format!(
+                            "Route exports of dynamically imported \"{}\" are now on its default export",
+                            source.value
+                        ),
+                        import_expr.span,
+                    ));
+                }
+            }
+        }
+    }
+
+    let (code, source_map) = apply_fixes(source_text, fixes, options.source_map)?;
+
+    Ok(CodemodOutput {
+        changed: code != source_text,
+        code,
+        source_map,
+        diagnostics,
+    })
+}
+
+/// Route object property an export of a route module moved to
+fn get_route_property(exported: &str, module: RouteModule) -> Option<&str> {
+    if exported == "default" {
+        Some("Component")
+    } else if KNOWN_REMIX_EXPORTS.contains(&exported)
+        || (module == RouteModule::Root && ROOT_ROUTE_EXPORTS.contains(&exported))
+    {
+        Some(rename_exports(Some(exported)).unwrap_or(exported))
+    } else {
+        None
+    }
+}
+
+struct RouteImport<'b, 'a> {
+    decl: &'b ImportDeclaration<'a>,
+    module: RouteModule,
+    program: &'b Program<'a>,
+    semantic: &'b Semantic<'a>,
+    source_text: &'a str,
+}
+
+/// Imported binding that now refers to a route object property
+struct MovedBinding<'a> {
+    local: &'a str,
+    symbol_id: Option<SymbolId>,
+    key: &'a str,
+    span: Span,
+}
+
+impl<'b, 'a> RouteImport<'b, 'a> {
+    /// Default import, if any, and the bindings of moved exports
+    fn get_moved_bindings(&self) -> Option<(Option<&'a str>, Vec<MovedBinding<'a>>)> {
+        let mut default = None;
+        let mut moved = vec![];
+        for specifier in self.decl.specifiers.iter().flatten() {
+            let (exported, local) = match specifier {
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => {
+                    default = Some(specifier.local.name.as_str());
+                    // `typeof usersRoute` of a type-only import, like the ones
+                    // the codemod adds for parent routes, is the route object
+                    if self.decl.import_kind.is_type() {
+                        continue;
+                    }
+                    ("default", &specifier.local)
+                }
+                ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                    let exported = match &specifier.imported {
+                        ModuleExportName::Identifier(ident) => ident.name.as_str(),
+                        ModuleExportName::StringLiteral(literal) => literal.value.as_str(),
+                    };
+                    (exported, &specifier.local)
+                }
+                // Can't tell which exports are used
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(_) => return None,
+            };
+            if let Some(key) = get_route_property(exported, self.module) {
+                moved.push(MovedBinding {
+                    local: local.name.as_str(),
+                    symbol_id: local.symbol_id.get(),
+                    key,
+                    span: specifier.span(),
+                });
+            }
+        }
+        Some((default, moved))
+    }
+
+    fn report(&self) -> Vec<Diagnostic> {
+        let Some((_, moved)) = self.get_moved_bindings() else {
+            return vec![self.namespace_diagnostic()];
+        };
+        moved
+            .into_iter()
+            .map(|binding| {
+                Diagnostic::new(
+                    "route-import",
+                    format!(
+                        "`{}` from \"{}\" is now `{}` on its default export",
+                        binding.local, self.decl.source.value, binding.key
+                    ),
+                    binding.span,
+                )
+            })
+            .collect()
+    }
+
+    fn namespace_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            "route-import",
+            format!(
+                "Namespace import of \"{}\", its route exports are now on `default`",
+                self.decl.source.value
+            ),
+            self.decl.span,
+        )
+    }
+
+    fn rewrite(&self, taken_names: &mut Vec<String>) -> (Vec<Fix<'a>>, Vec<Diagnostic>) {
+        let Some((default, moved)) = self.get_moved_bindings() else {
+            return (vec![], vec![self.namespace_diagnostic()]);
+        };
+        if moved.is_empty() {
+            return (vec![], vec![]);
+        }
+
+        let mut fixes = vec![];
+        let mut diagnostics = vec![];
+        let route = match default {
+            Some(name) => name.to_owned(),
+            None => self.get_unique_name(taken_names),
+        };
+        let mut kept = vec![];
+        let mut rewritten = 0;
+
+        for binding in moved.iter() {
+            // `export { loader }` can't point at a property
+            if self.is_exported(binding.local) {
+                diagnostics.push(Diagnostic::new(
+                    "route-import",
+                    format!(
+                        "`{}` from \"{}\" is exported again, it's now `{}` on the route's default export",
+                        binding.local, self.decl.source.value, binding.key
+                    ),
+                    binding.span,
+                ));
+                if Some(binding.local) != default {
+                    kept.push(binding.span);
+                }
+                continue;
+            }
+            fixes.extend(self.replace_references(binding, &format!("{}.{}", route, binding.key)));
+            rewritten += 1;
+        }
+        if rewritten == 0 {
+            return (fixes, diagnostics);
+        }
+
+        let named = self
+            .decl
+            .specifiers
+            .iter()
+            .flatten()
+            .filter_map(|specifier| match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(specifier) => Some(specifier.span),
+                _ => None,
+            })
+            .filter(|span| kept.contains(span) || !moved.iter().any(|b| b.span == *span))
+            .map(|span| span.source_text(self.source_text))
+            .collect::<Vec<_>>();
+
+        let source = self.decl.source.span.source_text(self.source_text);
+        let statement = match (self.decl.import_kind.is_type(), named.is_empty()) {
+            (_, true) => format!("import {}{} from {};", self.type_keyword(), route, source),
+            // Type-only imports can't have both default and named bindings
+            (true, false) => format!(
+                "import type {} from {};\nimport type {{ {} }} from {};",
+                route,
+                source,
+                named.join(", "),
+                source
+            ),
+            (false, false) => format!(
+                "import {}, {{ {} }} from {};",
+                route,
+                named.join(", "),
+                source
+            ),
+        };
+        fixes.push(Fix::insert(statement, self.decl.span).with_rule(
+            "route-import",
+            format!("Import the route object from {}", source),
+        ));
+
+        (fixes, diagnostics)
+    }
+
+    fn type_keyword(&self) -> &'static str {
+        if self.decl.import_kind.is_type() {
+            "type "
+        } else {
+            ""
+        }
+    }
+
+    /// Point every reference to the binding at `replacement`, keeping the key
+    /// of shorthand properties
+    fn replace_references(&self, binding: &MovedBinding, replacement: &str) -> Vec<Fix<'a>> {
+        let Some(symbol_id) = binding.symbol_id else {
+            return vec![];
+        };
+        self.semantic
+            .symbols()
+            .get_resolved_reference_ids(symbol_id)
+            .iter()
+            .map(|id| {
+                let reference = self.semantic.symbols().get_reference(*id);
+                let content = match self.semantic.nodes().parent_kind(reference.node_id()) {
+                    Some(AstKind::ObjectProperty(property)) if property.shorthand => {
+                        format!("{}: {}", binding.local, replacement)
+                    }
+                    _ => replacement.to_owned(),
+                };
+                Fix::insert(content, reference.span()).with_rule(
+                    "route-import",
+                    format!("Read `{}` from the route object", binding.local),
+                )
+            })
+            .collect()
+    }
+
+    fn is_exported(&self, local: &str) -> bool {
+        self.program.body.iter().any(|statement| match statement {
+            Statement::ExportNamedDeclaration(decl) if decl.source.is_none() => decl
+                .specifiers
+                .iter()
+                .any(|specifier| specifier.local.name() == local),
+            Statement::ExportDefaultDeclaration(decl) => {
+                decl.declaration.as_expression().is_some_and(
+                    |expr| matches!(expr, Expression::Identifier(ident) if ident.name == local),
+                )
+            }
+            _ => false,
+        })
+    }
+
+    /// Name for the route object based on the module path, like
+    /// `usersUserIdRoute` for `~/routes/users.$userId`
+    fn get_unique_name(&self, taken_names: &mut Vec<String>) -> String {
+        let name = get_route_binding_name(&self.decl.source.value);
+        let mut unique = name.clone();
+        let mut counter = 2;
+        while self
+            .semantic
+            .symbols()
+            .get_symbol_id_from_name(&unique)
+            .is_some()
+            || taken_names.contains(&unique)
+        {
+            unique = format!("{}{}", name, counter);
+            counter += 1;
+        }
+        taken_names.push(unique.clone());
+        unique
+    }
+}
+
+pub fn get_route_binding_name(source: &str) -> String {
+    let mut segments = source.trim_end_matches('/').rsplit('/').map(|segment| {
+        EXTENSIONS
+            .iter()
+            .find_map(|ext| segment.strip_suffix(&format!(".{}", ext)))
+            .unwrap_or(segment)
+    });
+    let mut segment = segments.next().unwrap_or_default();
+    // Folder routes, like `routes/users/route.tsx`
+    if segment == "route" || segment == "index" {
+        segment = segments.next().unwrap_or(segment);
+    }
+
+    let mut name = String::new();
+    for word in segment
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let mut chars = word.chars();
+        let first = chars.next().unwrap();
+        if name.is_empty() {
+            name.push(first.to_ascii_lowercase());
+        } else {
+            name.push(first.to_ascii_uppercase());
+        }
+        name.extend(chars);
+    }
+
+    if name.is_empty() {
+        "route".to_owned()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}Route", name)
+    } else {
+        format!("{}Route", name)
+    }
+}
+
+/// Relative import source of `to` in `from`, both route files relative to
+/// the app directory like in the routes manifest
+pub fn get_relative_import(from: &str, to: &str) -> String {
+    let to = strip_extension(to);
+    let mut from_dirs = from.split('/').collect::<Vec<_>>();
+    from_dirs.pop();
+    let to_segments = to.split('/').collect::<Vec<_>>();
+
+    let common = from_dirs
+        .iter()
+        .zip(to_segments.iter())
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(to_segments.len() - 1);
+    let up = from_dirs.len() - common;
+    let prefix = if up == 0 {
+        "./".to_owned()
+    } else {
+        "../".repeat(up)
+    };
+    format!("{}{}", prefix, to_segments[common..].join("/"))
+}
+
+/// Route of the routes manifest an import source in `importer` refers to,
+/// both relative to the app directory
+pub fn resolve_route_import<'r>(
+    source: &str,
+    importer: &str,
+    routes: &'r [ManifestRoute],
+) -> Option<&'r ManifestRoute> {
+    let mut segments = if let Some(path) = source.strip_prefix("~/") {
+        vec![path]
+    } else if source.starts_with("./") || source.starts_with("../") {
+        let mut segments = importer.split('/').collect::<Vec<_>>();
+        segments.pop();
+        segments.push(source);
+        segments
+    } else {
+        return None;
+    }
+    .into_iter()
+    .flat_map(|path| path.split('/'))
+    .collect::<Vec<_>>();
+
+    let mut normalized: Vec<&str> = vec![];
+    for segment in segments.drain(..) {
+        match segment {
+            "." | "" => {}
+            ".." => {
+                normalized.pop()?;
+            }
+            segment => normalized.push(segment),
+        }
+    }
+    let base = normalized.join("/");
+
+    let base = strip_extension(&base);
+    routes.iter().find(|route| {
+        let file = strip_extension(&route.file);
+        file == base || file == format!("{}/route", base) || file == format!("{}/index", base)
+    })
+}
+
+fn strip_extension(file: &str) -> &str {
+    EXTENSIONS
+        .iter()
+        .find_map(|ext| file.strip_suffix(&format!(".{}", ext)))
+        .unwrap_or(file)
+}
+
+/// File an import source refers to, for relative imports and the `~/` alias
+/// of the Remix templates
+pub fn resolve_import(source: &str, importer: &Path, app_dir: &Path) -> Option<PathBuf> {
+    let base = if let Some(path) = source.strip_prefix("~/") {
+        app_dir.join(path)
+    } else if source.starts_with("./") || source.starts_with("../") {
+        importer.parent()?.join(source)
+    } else {
+        return None;
+    };
+
+    let mut candidates = vec![base.clone()];
+    for ext in EXTENSIONS {
+        let mut with_ext = base.clone().into_os_string();
+        with_ext.push(format!(".{}", ext));
+        candidates.push(PathBuf::from(with_ext));
+    }
+    for file in ["route", "index"] {
+        for ext in EXTENSIONS {
+            candidates.push(base.join(format!("{}.{}", file, ext)));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .and_then(|candidate| candidate.canonicalize().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::SourceType;
+
+    use super::{
+        get_relative_import, get_route_binding_name, rewrite_route_imports, RouteImportMode,
+        RouteModule,
+    };
+    use crate::{
+        codemod::codemod,
+        codemod_models::CodemodOptions,
+        test_utils::{assert_output_snapshot, manifest_options, outdent},
+    };
+
+    #[test]
+    fn test_named_route_imports() {
+        let input = r#"
+            import { describe, expect, test } from "vitest";
+            import { loader, action, getUserName } from "~/routes/users.$userId";
+
+            const handlers = { loader, action };
+
+            test("loader", async () => {
+              const response = await loader({ params: { userId: "1" } });
+              expect(getUserName(response)).toBe("Ada");
+            });
+
+            type LoaderData = Awaited<ReturnType<typeof loader>>;
+        "#;
+        assert_snapshot("named_route_imports", input, RouteImportMode::Rewrite);
+    }
+
+    #[test]
+    fn test_default_route_import() {
+        let input = r#"
+            import type { Meta } from "@storybook/react";
+            import UsersPage, { meta as usersMeta } from "~/routes/users";
+            import { Button } from "~/components/button";
+
+            export default {
+              component: UsersPage,
+              title: usersMeta()[0].title,
+            } satisfies Meta;
+
+            export const Page = () => (
+              <UsersPage>
+                <Button />
+              </UsersPage>
+            );
+        "#;
+        assert_snapshot("default_route_import", input, RouteImportMode::Rewrite);
+    }
+
+    #[test]
+    fn test_root_layout_import() {
+        let input = r#"
+            import { Layout, loader } from "~/root";
+            import { Layout as UsersLayout } from "~/routes/users";
+
+            export const Page = () => (
+              <Layout>
+                <UsersLayout />
+              </Layout>
+            );
+
+            export { loader };
+        "#;
+        assert_snapshot("root_layout_import", input, RouteImportMode::Rewrite);
+    }
+
+    #[test]
+    fn test_unsupported_route_imports() {
+        let input = r#"
+            import * as users from "~/routes/users";
+            import { loader } from "~/routes/admin";
+            export { action } from "~/routes/users";
+            export * from "~/routes/settings";
+
+            export { loader };
+
+            const lazyRoute = () => import("~/routes/settings");
+        "#;
+        assert_snapshot("unsupported_route_imports", input, RouteImportMode::Rewrite);
+    }
+
+    #[test]
+    fn test_report_route_imports() {
+        let input = r#"
+            import UsersPage, { loader, getUserName } from "~/routes/users";
+            import { Button } from "~/components/button";
+        "#;
+        assert_snapshot("report_route_imports", input, RouteImportMode::Report);
+    }
+
+    #[test]
+    fn test_codemod_route_imports() {
+        // Parent route types added by the codemod already point at the route object
+        let input = outdent(
+            r#"
+            import type { MetaFunction } from "@remix-run/node";
+            import type { loader as rootLoader } from "~/root";
+            import type { loader as usersLoader } from "./users";
+
+            export const meta: MetaFunction<
+              unknown,
+              { root: typeof rootLoader; "routes/users": typeof usersLoader }
+            > = ({ matches }) => [{ title: String(matches.length) }];
+
+            export default function User() {
+              return null;
+            }
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let migrated = codemod(&input, source_type, &manifest_options())
+            .unwrap()
+            .code;
+        assert!(migrated.contains("typeof usersRoute;"));
+
+        let get_route_module = |source: &str| match source {
+            "../root" => Some(RouteModule::Root),
+            "./users" => Some(RouteModule::Route),
+            _ => None,
+        };
+        let output = rewrite_route_imports(
+            &migrated,
+            source_type,
+            &CodemodOptions::default(),
+            RouteImportMode::Rewrite,
+            &get_route_module,
+        )
+        .unwrap();
+        assert_eq!(output.code, migrated);
+        assert!(output.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_line_endings_without_route_imports() {
+        let input = "const a = 1;\nconst b = `x\r\ny`;\r\nexport const c = 2;\r\n";
+        let source_type = SourceType::from_path("path/to/users.test.ts").unwrap();
+        let output = rewrite_route_imports(
+            input,
+            source_type,
+            &CodemodOptions::default(),
+            RouteImportMode::Rewrite,
+            &|_| Some(RouteModule::Route),
+        )
+        .unwrap();
+        assert_eq!(output.code, input);
+        assert!(!output.changed);
+    }
+
+    #[test]
+    fn test_route_binding_name() {
+        assert_eq!(
+            get_route_binding_name("~/routes/users.$userId"),
+            "usersUserIdRoute"
+        );
+        assert_eq!(get_route_binding_name("./routes/_index.tsx"), "indexRoute");
+        assert_eq!(
+            get_route_binding_name("../routes/admin.settings/route"),
+            "adminSettingsRoute"
+        );
+        assert_eq!(get_route_binding_name("~/routes/$"), "route");
+        assert_eq!(get_route_binding_name("~/routes/404"), "_404Route");
+    }
+
+    #[test]
+    fn test_relative_import() {
+        assert_eq!(
+            get_relative_import("routes/users.$userId.tsx", "routes/users.tsx"),
+            "./users"
+        );
+        assert_eq!(
+            get_relative_import("routes/users.tsx", "root.tsx"),
+            "../root"
+        );
+        assert_eq!(
+            get_relative_import("routes/users.$userId/route.tsx", "routes/users/route.tsx"),
+            "../users/route"
+        );
+        assert_eq!(
+            get_relative_import("root.tsx", "routes/_index.tsx"),
+            "./routes/_index"
+        );
+    }
+
+    /// Output code followed by the diagnostics
+    fn assert_snapshot(name: &str, input: &str, mode: RouteImportMode) {
+        let input = outdent(input);
+        let source_type = SourceType::from_path("path/to/users.test.tsx").unwrap();
+        let options = CodemodOptions::default();
+        let get_route_module = |source: &str| {
+            if source == "~/root" {
+                Some(RouteModule::Root)
+            } else {
+                source
+                    .starts_with("~/routes/")
+                    .then_some(RouteModule::Route)
+            }
+        };
+        let output =
+            rewrite_route_imports(&input, source_type, &options, mode, &get_route_module).unwrap();
+        let diagnostics = output
+            .diagnostics
+            .iter()
+            .map(|d| format!("// {}\n", d))
+            .collect::<String>();
+        assert_output_snapshot(name, &input, &format!("{}\n{}", output.code, diagnostics));
+    }
+}
@@ -0,0 +1,111 @@
+//! Matching URLs against the URL patterns of the route tree, like `/users/:userId`
+
+use crate::codemod_models::ManifestRoute;
+
+/// Route a URL like `/users/123?tab=posts` goes to, and the values of its
+/// params. Static segments rank above params, and params above splats.
+pub fn find_route<'r>(
+    routes: &'r [ManifestRoute],
+    url: &str,
+) -> Option<(&'r ManifestRoute, Vec<(String, String)>)> {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    routes
+        .iter()
+        .filter_map(|route| {
+            let pattern = route.path.as_deref()?;
+            match_path(pattern, url).map(|params| (route, params))
+        })
+        .max_by_key(|(route, _)| rank(route.path.as_deref().unwrap_or_default()))
+}
+
+/// Values of the params of `pattern` in `url`, `None` if it doesn't match
+pub fn match_path(pattern: &str, url: &str) -> Option<Vec<(String, String)>> {
+    let pattern = split_segments(pattern);
+    let url = split_segments(url);
+    let mut params = vec![];
+    match_segments(&pattern, &url, &mut params).then_some(params)
+}
+
+fn split_segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn match_segments(pattern: &[&str], url: &[&str], params: &mut Vec<(String, String)>) -> bool {
+    let Some((segment, pattern_rest)) = pattern.split_first() else {
+        return url.is_empty();
+    };
+    if *segment == "*" {
+        params.push(("*".to_owned(), url.join("/")));
+        return true;
+    }
+
+    let (segment, optional) = match segment.strip_suffix('?') {
+        Some(segment) => (segment, true),
+        None => (*segment, false),
+    };
+    if let Some((value, url_rest)) = url.split_first() {
+        let matched = segment
+            .strip_prefix(':')
+            .map(|name| (name.to_owned(), value.to_string()));
+        if matched.is_some() || segment.eq_ignore_ascii_case(value) {
+            let len = params.len();
+            params.extend(matched);
+            if match_segments(pattern_rest, url_rest, params) {
+                return true;
+            }
+            params.truncate(len);
+        }
+    }
+    optional && match_segments(pattern_rest, url, params)
+}
+
+/// Specificity of a pattern, by the kinds of its segments
+fn rank(pattern: &str) -> (usize, usize, bool) {
+    let segments = split_segments(pattern);
+    let is_static = |segment: &&&str| !segment.starts_with(':') && **segment != "*";
+    (
+        segments.iter().filter(is_static).count(),
+        segments.len(),
+        !segments.contains(&"*"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_route, match_path};
+    use crate::test_utils::manifest_options;
+
+    #[test]
+    fn test_match_path() {
+        assert_eq!(
+            match_path("/users/:userId", "/users/123"),
+            Some(vec![("userId".to_owned(), "123".to_owned())])
+        );
+        assert_eq!(match_path("/users/:userId", "/users"), None);
+        assert_eq!(match_path("/users", "/Users/"), Some(vec![]));
+        assert_eq!(match_path("/:lang?/about", "/about"), Some(vec![]),);
+        assert_eq!(
+            match_path("/:lang?/about", "/en/about"),
+            Some(vec![("lang".to_owned(), "en".to_owned())])
+        );
+        assert_eq!(
+            match_path("/files/*", "/files/a/b.txt"),
+            Some(vec![("*".to_owned(), "a/b.txt".to_owned())])
+        );
+    }
+
+    #[test]
+    fn test_find_route() {
+        let options = manifest_options();
+        let find = |url| find_route(&options.routes, url).map(|(route, _)| route.id.as_str());
+        assert_eq!(
+            find("/users/123/edit?tab=name"),
+            Some("routes/users.$userId_.edit")
+        );
+        assert_eq!(find("/users/123"), Some("routes/users.$userId"));
+        assert_eq!(find("/"), Some("routes/_index"));
+        assert_eq!(find("/teams/123"), None);
+    }
+}
@@ -0,0 +1,436 @@
+//! Byte level mappings between transformed and original source text,
+//! and their serialization as Source Map v3.
+
+use oxc_span::Span;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    generated: u32,
+    original: Option<u32>,
+    len: u32,
+}
+
+/// Maps contiguous byte ranges of a generated text back to byte offsets of the
+/// text it was generated from. Ranges without an origin are inserted code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteMap {
+    segments: Vec<Segment>,
+}
+
+impl ByteMap {
+    pub fn identity(len: u32) -> Self {
+        let mut map = Self::default();
+        map.push(Some(0), len);
+        map
+    }
+
+    fn generated_len(&self) -> u32 {
+        self.segments.last().map_or(0, |s| s.generated + s.len)
+    }
+
+    /// Append a range to the end of the generated text
+    pub fn push(&mut self, original: Option<u32>, len: u32) {
+        if len == 0 {
+            return;
+        }
+        let generated = self.generated_len();
+        if let Some(last) = self.segments.last_mut() {
+            let continues = match (last.original, original) {
+                (Some(a), Some(b)) => a + last.len == b,
+                (None, None) => true,
+                _ => false,
+            };
+            if continues {
+                last.len += len;
+                return;
+            }
+        }
+        self.segments.push(Segment {
+            generated,
+            original,
+            len,
+        });
+    }
+
+    /// Append another map, as if its generated text was appended to ours
+    pub fn extend(&mut self, other: &ByteMap) {
+        for segment in other.segments.iter() {
+            self.push(segment.original, segment.len);
+        }
+    }
+
+    /// Given `self` mapping C -> B and `earlier` mapping B -> A, produce C -> A
+    pub fn compose(&self, earlier: &ByteMap) -> ByteMap {
+        let mut map = ByteMap::default();
+        for segment in self.segments.iter() {
+            let Some(original) = segment.original else {
+                map.push(None, segment.len);
+                continue;
+            };
+
+            let mut pos = original;
+            let end = original + segment.len;
+            let mut i = earlier
+                .segments
+                .partition_point(|s| s.generated + s.len <= pos);
+            while pos < end {
+                match earlier.segments.get(i) {
+                    Some(s) if s.generated <= pos => {
+                        let len = (s.generated + s.len).min(end) - pos;
+                        map.push(s.original.map(|o| o + pos - s.generated), len);
+                        pos += len;
+                        i += 1;
+                    }
+                    _ => {
+                        map.push(None, end - pos);
+                        pos = end;
+                    }
+                }
+            }
+        }
+        map
+    }
+
+    /// Offset in the original text of a generated offset, if it was copied from there
+    pub fn original_offset(&self, generated: u32) -> Option<u32> {
+        let i = self
+            .segments
+            .partition_point(|s| s.generated + s.len <= generated);
+        let segment = self.segments.get(i).filter(|s| s.generated <= generated)?;
+        segment.original.map(|o| o + generated - segment.generated)
+    }
+
+    /// Span in the original text of a generated span. Inserted code maps to
+    /// an empty span where it was inserted, after the nearest copied range.
+    pub fn original_span(&self, span: Span) -> Span {
+        if let Some(start) = self.original_offset(span.start) {
+            return Span::new(start, start + span.size());
+        }
+        let i = self
+            .segments
+            .partition_point(|s| s.generated + s.len <= span.start);
+        let before = self.segments[..i]
+            .iter()
+            .rev()
+            .find_map(|s| s.original.map(|o| o + s.len));
+        let after = || self.segments[i..].iter().find_map(|s| s.original);
+        let position = before.or_else(after).unwrap_or_default();
+        Span::new(position, position)
+    }
+
+    /// Serialize as a Source Map v3 object
+    pub fn to_source_map(
+        &self,
+        generated_text: &str,
+        original_text: &str,
+        file: &str,
+        source: &str,
+    ) -> Value {
+        let generated_lines = LineIndex::new(generated_text);
+        let original_lines = LineIndex::new(original_text);
+
+        let mut mappings = String::new();
+        let mut current_line = 0;
+        let mut previous_generated_column = 0;
+        let mut previous_original = (0, 0);
+        let mut first_in_line = true;
+
+        for segment in self.segments.iter() {
+            let start = segment.generated as usize;
+            let end = start + segment.len as usize;
+
+            // Every line needs its own mapping, so split the range at line starts
+            let mut points = vec![start];
+            for (i, byte) in generated_text.as_bytes()[start..end].iter().enumerate() {
+                if *byte == b'\n' && start + i + 1 < end {
+                    points.push(start + i + 1);
+                }
+            }
+
+            for point in points {
+                let (line, column) = generated_lines.position(generated_text, point);
+                if line != current_line {
+                    for _ in current_line..line {
+                        mappings.push(';');
+                    }
+                    current_line = line;
+                    previous_generated_column = 0;
+                    first_in_line = true;
+                }
+                if !first_in_line {
+                    mappings.push(',');
+                }
+                first_in_line = false;
+
+                encode_vlq(
+                    &mut mappings,
+                    column as i64 - previous_generated_column as i64,
+                );
+                previous_generated_column = column;
+
+                if let Some(original) = segment.original {
+                    let original = original as usize + point - start;
+                    let (original_line, original_column) =
+                        original_lines.position(original_text, original);
+                    encode_vlq(&mut mappings, 0);
+                    encode_vlq(
+                        &mut mappings,
+                        original_line as i64 - previous_original.0 as i64,
+                    );
+                    encode_vlq(
+                        &mut mappings,
+                        original_column as i64 - previous_original.1 as i64,
+                    );
+                    previous_original = (original_line, original_column);
+                }
+            }
+        }
+
+        json!({
+            "version": 3,
+            "file": file,
+            "sources": [source],
+            "sourcesContent": [original_text],
+            "names": [],
+            "mappings": mappings,
+        })
+    }
+}
+
+/// Text with a [ByteMap] describing where each of its ranges came from
+#[derive(Debug, Clone, Default)]
+pub struct MappedText {
+    pub text: String,
+    pub map: ByteMap,
+}
+
+impl MappedText {
+    /// Append inserted text that has no origin
+    pub fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.map.push(None, text.len() as u32);
+    }
+
+    /// Append text copied from the original source, starting at `original_start`
+    pub fn push_source(&mut self, text: &str, original_start: u32) {
+        self.text.push_str(text);
+        self.map.push(Some(original_start), text.len() as u32);
+    }
+
+    pub fn push_mapped(&mut self, other: &MappedText) {
+        self.text.push_str(&other.text);
+        self.map.extend(&other.map);
+    }
+
+    /// Same as `indent::indent_all_by`, keeping the mappings intact
+    pub fn indent_all_by(&self, number_of_spaces: usize) -> MappedText {
+        let indentation = " ".repeat(number_of_spaces);
+        let mut output = MappedText::default();
+        let mut at_line_start = true;
+
+        for segment in self.map.segments.iter() {
+            let start = segment.generated as usize;
+            let text = &self.text[start..start + segment.len as usize];
+            let mut offset = 0;
+            for piece in text.split_inclusive('\n') {
+                if at_line_start && !piece.starts_with('\n') {
+                    output.push_str(&indentation);
+                }
+                match segment.original {
+                    Some(original) => output.push_source(piece, original + offset),
+                    None => output.push_str(piece),
+                }
+                offset += piece.len() as u32;
+                at_line_start = piece.ends_with('\n');
+            }
+        }
+
+        output
+    }
+
+    /// Remove up to `number_of_spaces` leading spaces from every line but the first
+    pub fn dedent_by(&self, number_of_spaces: usize) -> MappedText {
+        let mut output = MappedText::default();
+        let mut to_strip = 0;
+
+        for segment in self.map.segments.iter() {
+            let start = segment.generated as usize;
+            let text = &self.text[start..start + segment.len as usize];
+            let push = |output: &mut MappedText, from: usize, to: usize| match segment.original {
+                Some(original) => output.push_source(&text[from..to], original + from as u32),
+                None => output.push_str(&text[from..to]),
+            };
+
+            let mut run_start = 0;
+            for (i, byte) in text.bytes().enumerate() {
+                if to_strip > 0 && byte == b' ' {
+                    push(&mut output, run_start, i);
+                    run_start = i + 1;
+                    to_strip -= 1;
+                    continue;
+                }
+                to_strip = if byte == b'\n' { number_of_spaces } else { 0 };
+            }
+            push(&mut output, run_start, text.len());
+        }
+
+        output
+    }
+}
+
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        for (i, byte) in text.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        Self { line_starts }
+    }
+
+    /// Zero-based line and UTF-16 column of a byte offset
+    fn position(&self, text: &str, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let column = text[line_start..offset].encode_utf16().count();
+        (line, column)
+    }
+}
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_vlq(output: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        output.push(BASE64_CHARS[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::{SourceType, Span};
+
+    use super::{encode_vlq, ByteMap, MappedText};
+    use crate::{codemod::codemod, codemod_models::CodemodOptions};
+
+    #[test]
+    fn test_encode_vlq() {
+        let mut output = String::new();
+        for value in [0, 1, -1, 15, 16, -16, 123456] {
+            encode_vlq(&mut output, value);
+            output.push(',');
+        }
+        assert_eq!(output, "A,C,D,e,gB,hB,gkxH,");
+    }
+
+    #[test]
+    fn test_compose() {
+        // "abcdef" -> "cdef" -> "Xcd"
+        let mut first = ByteMap::default();
+        first.push(Some(2), 4);
+        let mut second = ByteMap::default();
+        second.push(None, 1);
+        second.push(Some(0), 2);
+
+        let mut expected = ByteMap::default();
+        expected.push(None, 1);
+        expected.push(Some(2), 2);
+        assert_eq!(second.compose(&first), expected);
+    }
+
+    #[test]
+    fn test_original_span() {
+        // "abcdef" -> "abXYZef"
+        let mut map = ByteMap::default();
+        map.push(Some(0), 2);
+        map.push(None, 3);
+        map.push(Some(4), 2);
+
+        assert_eq!(map.original_span(Span::new(5, 6)), Span::new(4, 5));
+        assert_eq!(map.original_span(Span::new(3, 4)), Span::new(2, 2));
+
+        let mut map = ByteMap::default();
+        map.push(None, 3);
+        map.push(Some(2), 2);
+        assert_eq!(map.original_span(Span::new(0, 3)), Span::new(2, 2));
+    }
+
+    #[test]
+    fn test_indent_all_by() {
+        let mut text = MappedText::default();
+        text.push_str("a(");
+        text.push_source("{\n\n  b\n}", 10);
+        text.push_str(")\n");
+
+        let indented = text.indent_all_by(2);
+        assert_eq!(indented.text, "  a({\n\n    b\n  })\n");
+
+        let mut expected = ByteMap::default();
+        expected.push(None, 4);
+        expected.push(Some(10), 3);
+        expected.push(None, 2);
+        expected.push(Some(13), 4);
+        expected.push(None, 2);
+        expected.push(Some(17), 1);
+        expected.push(None, 2);
+        assert_eq!(indented.map, expected);
+    }
+
+    #[test]
+    fn test_codemod_source_map() {
+        let input = "import { useLoaderData } from \"@remix-run/react\";\n\nexport async function loader({ params }: LoaderFunctionArgs) {\n  return { id: params.id };\n}\n\nexport default function Route() {\n  const data = useLoaderData<typeof loader>();\n  return <h1>{data.id}</h1>;\n}\n".to_owned();
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        let options = CodemodOptions {
+            source_map: true,
+            ..Default::default()
+        };
+        let output = codemod(&input, source_type, &options).unwrap();
+        let map = output.source_map.unwrap();
+
+        // Every mapped range must point at identical text in the original
+        for segment in map.segments.iter() {
+            if let Some(original) = segment.original {
+                let generated = segment.generated as usize;
+                let original = original as usize;
+                let len = segment.len as usize;
+                assert_eq!(
+                    &output.code[generated..generated + len],
+                    &input[original..original + len]
+                );
+            }
+        }
+
+        // The moved loader body is mapped back to its original position
+        let body = "{\n    return { id: params.id };\n  }";
+        let generated = output.code.find(body).unwrap() as u32;
+        let segment = map
+            .segments
+            .iter()
+            .find(|s| s.generated <= generated && generated < s.generated + s.len)
+            .unwrap();
+        let original = segment.original.unwrap() + generated - segment.generated;
+        assert_eq!(original as usize, input.find("{\n  return").unwrap());
+
+        let source_map = map.to_source_map(&output.code, &input, "file.tsx", "file.tsx");
+        assert_eq!(source_map["version"], 3);
+        assert_eq!(source_map["sources"][0], "file.tsx");
+    }
+}
@@ -0,0 +1,116 @@
+use std::cmp;
+
+use oxc_span::SourceType;
+
+use crate::{
+    codemod::codemod,
+    codemod_models::{CodemodError, CodemodOptions, CodemodOutput, Diagnostic, ManifestRoute},
+};
+
+/// Remove leading whitespace from each line, preserving relative indentation.
+/// Remove the first and the last lines.
+pub fn outdent(input: &str) -> String {
+    let length = input.len();
+    let mut output = String::with_capacity(length);
+
+    let mut base_indent = 0;
+
+    let input_body = skip_last(input.lines().skip(1));
+
+    for (i, line) in input_body.enumerate() {
+        if i == 0 {
+            base_indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        }
+
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        let indent = cmp::min(base_indent, indent);
+
+        output.push_str(&line[indent..]);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Snapshot the codemod output of a `.tsx` route and return its diagnostics
+pub fn assert_codemod_snapshot(
+    name: &str,
+    input: &str,
+    options: &CodemodOptions,
+) -> Vec<Diagnostic> {
+    assert_transform_snapshot(name, input, "path/to/file.tsx", options, codemod)
+}
+
+/// Snapshot the output of a transform like `codemod` or `revert` of the file
+/// at `path` and return its diagnostics
+pub fn assert_transform_snapshot(
+    name: &str,
+    input: &str,
+    path: &str,
+    options: &CodemodOptions,
+    transform: impl Fn(&str, SourceType, &CodemodOptions) -> Result<CodemodOutput, CodemodError>,
+) -> Vec<Diagnostic> {
+    let input = outdent(input);
+    let source_type = SourceType::from_path(path).unwrap();
+    let output = transform(&input, source_type, options).unwrap();
+    assert_output_snapshot(name, &input, &output.code);
+    output.diagnostics
+}
+
+/// Snapshot `output`, with the input it was generated from as description
+pub fn assert_output_snapshot(name: &str, input: &str, output: &str) {
+    insta::with_settings!({
+        prepend_module_to_snapshot => false,
+        description => input,
+    }, {
+        insta::assert_snapshot!(name, output);
+    });
+}
+
+/// Options for `routes/users.$userId.tsx` with its parent routes in the
+/// routes manifest
+pub fn manifest_options() -> CodemodOptions {
+    let routes = [
+        ("root", "root.tsx", None),
+        ("routes/_index", "routes/_index.tsx", Some("/")),
+        ("routes/users", "routes/users.tsx", Some("/users")),
+        (
+            "routes/users._index",
+            "routes/users._index.tsx",
+            Some("/users"),
+        ),
+        (
+            "routes/users.$userId",
+            "routes/users.$userId.tsx",
+            Some("/users/:userId"),
+        ),
+        (
+            "routes/users.$userId_.edit",
+            "routes/users.$userId_.edit.tsx",
+            Some("/users/:userId/edit"),
+        ),
+        ("routes/files.$", "routes/files.$.tsx", Some("/files/*")),
+        (
+            "routes/($lang).about",
+            "routes/($lang).about.tsx",
+            Some("/:lang?/about"),
+        ),
+    ];
+    CodemodOptions {
+        route_file: Some("routes/users.$userId.tsx".to_owned()),
+        routes: routes
+            .iter()
+            .map(|(id, file, path)| ManifestRoute {
+                id: id.to_string(),
+                file: file.to_string(),
+                path: path.map(|path| path.to_owned()),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn skip_last<T>(mut iter: impl Iterator<Item = T>) -> impl Iterator<Item = T> {
+    let last = iter.next();
+    iter.scan(last, |state, item| state.replace(item))
+}
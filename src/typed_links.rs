@@ -0,0 +1,364 @@
+//! Optional pass that rewrites the URLs of links, forms, navigations and
+//! redirects to the typed `href()` helper of react-router, e.g.
+//! `` `/users/${userId}` `` becomes `href("/users/:userId", { userId })`
+
+use oxc_ast::{
+    ast::{
+        Argument, BindingPatternKind, Expression, ImportDeclarationSpecifier, JSXAttributeItem,
+        JSXAttributeName, JSXAttributeValue, JSXElementName, Program, TemplateLiteral,
+    },
+    AstKind,
+};
+use oxc_semantic::Semantic;
+use oxc_span::{GetSpan, Span};
+use serde_json::Value;
+
+use crate::{
+    codemod_models::{Diagnostic, ManifestRoute},
+    fixer::Fix,
+    imports::{add_named_imports, get_import_declarations},
+    route_paths::find_route,
+};
+
+const LINK_MODULES: [&str; 6] = [
+    "@remix-run/react",
+    "@remix-run/node",
+    "@remix-run/cloudflare",
+    "@remix-run/server-runtime",
+    "react-router",
+    "react-router-dom",
+];
+
+/// Components and their prop with a URL
+const URL_PROPS: [(&str, &str); 3] = [("Link", "to"), ("NavLink", "to"), ("Form", "action")];
+
+/// Stands in for the expressions of a template literal while matching it
+const PLACEHOLDER: char = '\u{1}';
+
+/// A URL found in the source text, from a string or template literal
+struct Url<'b, 'a> {
+    literal: Literal<'b, 'a>,
+    span: Span,
+    /// JSX attribute values like `to="/users"` need braces around `href()`
+    in_attribute: bool,
+}
+
+enum Literal<'b, 'a> {
+    String(&'b str),
+    Template(&'b TemplateLiteral<'a>),
+}
+
+impl<'b, 'a> Url<'b, 'a> {
+    fn from_expression(expression: &'b Expression<'a>) -> Option<Self> {
+        let literal = match expression {
+            Expression::StringLiteral(literal) => Literal::String(&literal.value),
+            Expression::TemplateLiteral(template) => Literal::Template(template),
+            _ => return None,
+        };
+        Some(Self {
+            literal,
+            span: expression.span(),
+            in_attribute: false,
+        })
+    }
+}
+
+pub fn type_links<'b>(
+    program: &Program,
+    semantic: &Semantic,
+    source_text: &str,
+    routes: &[ManifestRoute],
+) -> (Vec<Fix<'b>>, Vec<Diagnostic>) {
+    let mut fixes = vec![];
+    let mut diagnostics = vec![];
+
+    // Local and imported names
+    let imports = get_import_declarations(program)
+        .into_iter()
+        .filter(|decl| LINK_MODULES.contains(&decl.source.value.as_str()))
+        .flat_map(|decl| decl.specifiers.iter().flatten())
+        .filter_map(|specifier| match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                Some((s.local.name.as_str(), s.imported.name().as_str()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let imported = |local: &str| {
+        imports
+            .iter()
+            .find(|(l, _)| *l == local)
+            .map(|(_, imported)| *imported)
+            .filter(|_| semantic.scopes().get_root_binding(local).is_some())
+    };
+    // Some other `href` is in the way
+    if semantic.scopes().get_root_binding("href").is_some() && imported("href") != Some("href") {
+        return (fixes, diagnostics);
+    }
+
+    let mut navigate_names = vec![];
+    for node in semantic.nodes().iter() {
+        let AstKind::VariableDeclarator(declarator) = node.kind() else {
+            continue;
+        };
+        if let (
+            BindingPatternKind::BindingIdentifier(binding),
+            Some(Expression::CallExpression(call_expr)),
+        ) = (&declarator.id.kind, &declarator.init)
+        {
+            if matches!(&call_expr.callee, Expression::Identifier(callee) if imported(&callee.name) == Some("useNavigate"))
+            {
+                navigate_names.push(binding.name.as_str());
+            }
+        }
+    }
+
+    let mut urls = vec![];
+    for node in semantic.nodes().iter() {
+        match node.kind() {
+            // `<Link to="/users">`
+            AstKind::JSXOpeningElement(element) => {
+                let JSXElementName::Identifier(name) = &element.name else {
+                    continue;
+                };
+                let Some((_, prop)) = imported(&name.name)
+                    .and_then(|imported| URL_PROPS.iter().find(|(c, _)| *c == imported))
+                else {
+                    continue;
+                };
+                for attribute in element.attributes.iter() {
+                    let JSXAttributeItem::Attribute(attribute) = attribute else {
+                        continue;
+                    };
+                    if !matches!(&attribute.name, JSXAttributeName::Identifier(ident) if ident.name == *prop)
+                    {
+                        continue;
+                    }
+                    match &attribute.value {
+                        Some(JSXAttributeValue::StringLiteral(literal)) => urls.push(Url {
+                            literal: Literal::String(&literal.value),
+                            span: literal.span,
+                            in_attribute: true,
+                        }),
+                        Some(JSXAttributeValue::ExpressionContainer(container)) => urls.extend(
+                            container
+                                .expression
+                                .as_expression()
+                                .and_then(Url::from_expression),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            // `navigate("/users")`, `redirect("/users")`
+            AstKind::CallExpression(call_expr) => {
+                let Expression::Identifier(callee) = &call_expr.callee else {
+                    continue;
+                };
+                if imported(&callee.name) != Some("redirect")
+                    && !navigate_names.contains(&callee.name.as_str())
+                {
+                    continue;
+                }
+                urls.extend(
+                    call_expr
+                        .arguments
+                        .first()
+                        .and_then(Argument::as_expression)
+                        .and_then(Url::from_expression),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    for url in urls {
+        let (path, values) = get_url_with_placeholders(&url.literal, source_text);
+        // Relative and external URLs
+        if !path.starts_with('/') || path.starts_with("//") {
+            continue;
+        }
+        let Some((route, params)) = find_route(routes, &path) else {
+            if values.is_empty() {
+                diagnostics.push(Diagnostic::new(
+                    "typed-links",
+                    format!(
+                        "`{}` doesn't match any route, it's probably a dead link",
+                        path
+                    ),
+                    url.span,
+                ));
+            }
+            continue;
+        };
+        // `href()` only builds the path
+        if path.contains(['?', '#']) {
+            continue;
+        }
+        let Some(text) = format_href(route.path.as_deref().unwrap_or_default(), &params, &values)
+        else {
+            continue;
+        };
+
+        let text = if url.in_attribute {
+            format!("{{{}}}", text)
+        } else {
+            text
+        };
+        fixes.push(Fix::insert(text, url.span).with_rule(
+            "typed-links",
+            format!("Use `href()` for {}", url.span.source_text(source_text)),
+        ));
+    }
+
+    if !fixes.is_empty() {
+        fixes.extend(add_named_imports(
+            program,
+            source_text,
+            "react-router",
+            &["href"],
+            false,
+        ));
+    }
+
+    (fixes, diagnostics)
+}
+
+/// URL of a string or template literal, with a placeholder for each of the
+/// template expressions, and the source text of those expressions
+fn get_url_with_placeholders<'s>(
+    literal: &Literal,
+    source_text: &'s str,
+) -> (String, Vec<&'s str>) {
+    match literal {
+        Literal::String(value) => (value.to_string(), vec![]),
+        Literal::Template(template) => {
+            let mut path = String::new();
+            let mut values = vec![];
+            for (i, quasi) in template.quasis.iter().enumerate() {
+                path.push_str(&quasi.value.raw);
+                if let Some(expression) = template.expressions.get(i) {
+                    path.push(PLACEHOLDER);
+                    path.push_str(&values.len().to_string());
+                    path.push(PLACEHOLDER);
+                    values.push(expression.span().source_text(source_text));
+                }
+            }
+            (path, values)
+        }
+    }
+}
+
+/// `href("/users/:userId", { userId })`, `None` if a param is only partly
+/// made of a template expression
+fn format_href(pattern: &str, params: &[(String, String)], values: &[&str]) -> Option<String> {
+    let pattern = Value::String(pattern.to_owned()).to_string();
+    if params.is_empty() {
+        return Some(format!("href({})", pattern));
+    }
+
+    let mut properties = vec![];
+    for (name, value) in params {
+        let key = if name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+        {
+            name.clone()
+        } else {
+            Value::String(name.clone()).to_string()
+        };
+        let property = match value
+            .strip_prefix(PLACEHOLDER)
+            .and_then(|value| value.strip_suffix(PLACEHOLDER))
+            .and_then(|index| index.parse::<usize>().ok())
+        {
+            Some(index) if *values.get(index)? == key => key,
+            Some(index) => format!("{}: {}", key, values.get(index)?),
+            None if value.contains(PLACEHOLDER) => return None,
+            None => format!("{}: {}", key, Value::String(value.clone())),
+        };
+        properties.push(property);
+    }
+    Some(format!(
+        "href({}, {{ {} }})",
+        pattern,
+        properties.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codemod_models::CodemodOptions,
+        test_utils::{assert_codemod_snapshot, manifest_options},
+    };
+
+    #[test]
+    fn test_typed_links() {
+        let input = r#"
+            import { redirect } from "@remix-run/node";
+            import { Form, Link, NavLink, useNavigate, useParams } from "@remix-run/react";
+
+            export async function action({ params }) {
+              throw redirect(`/users/${params.userId}/edit`);
+            }
+
+            export default function User() {
+              const { userId, lang, path } = useParams();
+              const navigate = useNavigate();
+              return (
+                <>
+                  <Link to="/users">Users</Link>
+                  <NavLink to={`/users/${userId}`}>Profile</NavLink>
+                  <Link to={`/${lang}/about`}>About</Link>
+                  <Link to={`/files/${path}`}>File</Link>
+                  <Link to="/users/123?tab=posts">Posts</Link>
+                  <Link to={`/users/user-${userId}`}>Prefixed</Link>
+                  <Link to="edit">Relative</Link>
+                  <Form action="/users/123/edit" method="post" />
+                  <button onClick={() => navigate("/")}>Home</button>
+                </>
+              );
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("typed_links", input, &typed_links_options());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_dead_links() {
+        let input = r#"
+            import { Link as RemixLink, useParams } from "@remix-run/react";
+
+            export default function User() {
+              const { teamId } = useParams();
+              return (
+                <>
+                  <RemixLink to="/user/123">User</RemixLink>
+                  <RemixLink to={`/teams/${teamId}`}>Team</RemixLink>
+                  <RemixLink to="https://remix.run">Remix</RemixLink>
+                </>
+              );
+            }
+        "#;
+        let diagnostics = assert_codemod_snapshot("dead_links", input, &typed_links_options());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "7:21 `/user/123` doesn't match any route, it's probably a dead link (typed-links)"
+            ]
+        );
+    }
+
+    fn typed_links_options() -> CodemodOptions {
+        CodemodOptions {
+            typed_links: true,
+            rewrite_imports: true,
+            ..manifest_options()
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! React Router v7 output target: keep the named route exports and type them
+//! with the per-route generated `Route.*` types instead of using defineRoute.
+
+use oxc_allocator::Allocator;
+use oxc_ast::{
+    ast::{
+        Declaration, ExportDefaultDeclarationKind, ExportNamedDeclaration, Expression,
+        FormalParameters, FunctionBody, TSType, TSTypeAnnotation, TSTypeName,
+    },
+    AstKind,
+};
+use oxc_parser::Parser;
+use oxc_semantic::SemanticBuilder;
+use oxc_span::{GetSpan, SourceType, Span};
+
+use std::process;
+
+use crate::{
+    codemod::{
+        apply_fixes, extract_hook_fix, get_declaration_function, get_hook_declarators,
+        get_named_export_name, map_diagnostic, merge_component_params, run_transform,
+    },
+    codemod_models::{CodemodError, CodemodOptions, CodemodOutput},
+    data_hooks::HookMatcher,
+    export_specifiers::get_movable_declaration,
+    fixer::Fix,
+    imports::{add_named_imports, get_imports_only_used_in, remove_named_imports},
+    package_imports::rewrite_package_imports,
+};
+
+pub fn typegen(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
+    run_transform(source_text, source_type, options, transform)
+}
+
+fn transform(
+    source_text: &str,
+    source_type: SourceType,
+    options: &CodemodOptions,
+) -> Result<CodemodOutput, CodemodError> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+
+    if !ret.errors.is_empty() {
+        for error in ret.errors {
+            let error = error.with_source_code(source_text.to_string());
+            println!("{error:?}");
+        }
+        process::exit(1);
+    }
+
+    let semantic_ret = SemanticBuilder::new(source_text, source_type)
+        .with_trivias(ret.trivias)
+        .build(&ret.program);
+
+    let mut code_fixes = vec![];
+    let is_typescript = source_type.is_typescript();
+    let hooks = HookMatcher::new(&ret.program, &options.data_hooks);
+
+    for node in semantic_ret.semantic.nodes().iter() {
+        match node.kind() {
+            AstKind::ExportNamedDeclaration(named_export) if is_typescript => {
+                if let Some(name) = get_named_export_name(node) {
+                    code_fixes.extend(get_export_type_fixes(named_export, name, source_text));
+                }
+            }
+            AstKind::ExportDefaultDeclaration(default_export) => {
+                let component = match &default_export.declaration {
+                    ExportDefaultDeclarationKind::FunctionDeclaration(decl) => {
+                        decl.body.as_ref().map(|body| (&*decl.params, &**body))
+                    }
+                    ExportDefaultDeclarationKind::ArrowFunctionExpression(arrow_func) => {
+                        Some((&*arrow_func.params, &*arrow_func.body))
+                    }
+                    // `export default Page`, unless `Page` is also used elsewhere
+                    ExportDefaultDeclarationKind::Identifier(ident) => get_movable_declaration(
+                        &ret.program,
+                        &semantic_ret.semantic,
+                        &ident.name,
+                        1,
+                    )
+                    .and_then(|(_, declaration)| get_declaration_function(declaration)),
+                    _ => None,
+                };
+                if let Some((params, body)) = component {
+                    code_fixes.extend(get_component_fixes(
+                        params,
+                        body,
+                        source_text,
+                        &hooks,
+                        is_typescript,
+                    )?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Hooks and types replaced by the generated route types
+    let replaced = code_fixes
+        .iter()
+        .filter(|fix| {
+            fix.rule_id == Some("extract-hook") || fix.rule_id == Some("route-type-annotation")
+        })
+        .map(|fix| fix.span)
+        .collect::<Vec<_>>();
+    let unused = get_imports_only_used_in(&ret.program, &semantic_ret.semantic, &replaced);
+    if !unused.is_empty() {
+        code_fixes.extend(remove_named_imports(&ret.program, source_text, &unused));
+    }
+
+    let uses_route_types = code_fixes
+        .iter()
+        .any(|fix| fix.rule_id == Some("route-type-annotation"));
+    if uses_route_types {
+        let route_name = options.route_name.as_deref().unwrap_or("route");
+        code_fixes.extend(add_named_imports(
+            &ret.program,
+            source_text,
+            &format!("./+types/{}", route_name),
+            &["Route"],
+            true,
+        ));
+    }
+
+    if !options.rewrite_imports {
+        let (code, source_map) = apply_fixes(source_text, code_fixes, options.source_map)?;
+        return Ok(CodemodOutput {
+            changed: code != source_text,
+            code,
+            source_map,
+            diagnostics: vec![],
+        });
+    }
+
+    // Imports are rewritten once the unused ones are gone, diagnostics are
+    // mapped back to the original text
+    let (code, first_pass_map) = apply_fixes(source_text, code_fixes, true)?;
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, &code, source_type).parse();
+    let (import_fixes, diagnostics) = rewrite_package_imports(&ret.program, &code);
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|diagnostic| map_diagnostic(diagnostic, &first_pass_map))
+        .collect();
+    let (code, second_pass_map) = apply_fixes(&code, import_fixes, options.source_map)?;
+
+    Ok(CodemodOutput {
+        changed: code != source_text,
+        code,
+        source_map: second_pass_map
+            .zip(first_pass_map)
+            .map(|(second, first)| second.compose(&first)),
+        diagnostics,
+    })
+}
+
+/// Generated type of the first argument of each route export
+fn get_args_type(name: &str) -> Option<&'static str> {
+    match name {
+        "loader" => Some("Route.LoaderArgs"),
+        "action" => Some("Route.ActionArgs"),
+        "clientLoader" => Some("Route.ClientLoaderArgs"),
+        "clientAction" => Some("Route.ClientActionArgs"),
+        "meta" => Some("Route.MetaArgs"),
+        "headers" => Some("Route.HeadersArgs"),
+        _ => None,
+    }
+}
+
+/// Generated replacement for a whole-function type, like `MetaFunction<typeof loader>`
+fn get_function_type(name: &str) -> Option<&'static str> {
+    match name {
+        "MetaFunction" => Some("Route.MetaFunction"),
+        "LinksFunction" => Some("Route.LinksFunction"),
+        "HeadersFunction" => Some("Route.HeadersFunction"),
+        _ => None,
+    }
+}
+
+fn annotation_fix<'a>(content: String, span: Span) -> Fix<'a> {
+    Fix::insert(content, span).with_rule(
+        "route-type-annotation",
+        "Use the generated route type annotation",
+    )
+}
+
+fn get_export_type_fixes<'a>(
+    named_export: &ExportNamedDeclaration,
+    name: &str,
+    source_text: &str,
+) -> Vec<Fix<'a>> {
+    let (params, declarator_annotation) = match &named_export.declaration {
+        Some(Declaration::FunctionDeclaration(decl)) => (&*decl.params, None),
+        Some(Declaration::VariableDeclaration(decl)) if decl.declarations.len() == 1 => {
+            let declarator = &decl.declarations[0];
+            match &declarator.init {
+                Some(Expression::ArrowFunctionExpression(arrow_func)) => (
+                    &*arrow_func.params,
+                    declarator.id.type_annotation.as_deref(),
+                ),
+                Some(Expression::FunctionExpression(func)) => {
+                    (&*func.params, declarator.id.type_annotation.as_deref())
+                }
+                _ => return vec![],
+            }
+        }
+        _ => return vec![],
+    };
+
+    // `export const meta: MetaFunction<typeof loader> = ...`
+    if let Some(annotation) = declarator_annotation {
+        return match get_type_reference_name(annotation).and_then(get_function_type) {
+            Some(function_type) => vec![annotation_fix(
+                format!(": {}", function_type),
+                annotation.span,
+            )],
+            None => vec![],
+        };
+    }
+
+    let Some(args_type) = get_args_type(name) else {
+        return vec![];
+    };
+    match params.items.first() {
+        Some(param) => match &param.pattern.type_annotation {
+            Some(annotation) => vec![annotation_fix(format!(": {}", args_type), annotation.span)],
+            None => vec![annotate_first_param(params, args_type, source_text)],
+        },
+        None => vec![],
+    }
+}
+
+/// Insert a type annotation after the first param, adding parentheses to
+/// arrow functions that omit them, like `args => ...`
+fn annotate_first_param<'a>(
+    params: &FormalParameters,
+    type_name: &str,
+    source_text: &str,
+) -> Fix<'a> {
+    let param_span = params.items[0].pattern.kind.span();
+    if source_text[params.span.start as usize..].starts_with('(') {
+        annotation_fix(
+            format!(": {}", type_name),
+            Span::new(param_span.end, param_span.end),
+        )
+    } else {
+        annotation_fix(
+            format!("({}: {})", param_span.source_text(source_text), type_name),
+            param_span,
+        )
+    }
+}
+
+fn get_type_reference_name<'a>(annotation: &'a TSTypeAnnotation) -> Option<&'a str> {
+    match &annotation.type_annotation {
+        TSType::TSTypeReference(reference) => match &reference.type_name {
+            TSTypeName::IdentifierReference(ident) => Some(ident.name.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Move `useLoaderData` and `useActionData` results into `Route.ComponentProps`
+fn get_component_fixes<'a>(
+    params: &FormalParameters,
+    body: &FunctionBody,
+    source_text: &str,
+    hooks: &HookMatcher,
+    is_typescript: bool,
+) -> Result<Vec<Fix<'a>>, CodemodError> {
+    let hooks = get_hook_declarators(body, source_text, hooks);
+    if hooks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut fixes = vec![];
+    for (hook, span) in hooks.iter() {
+        fixes.push(extract_hook_fix(hook, *span));
+    }
+    let hooks = hooks.into_iter().map(|(hook, _)| hook).collect();
+    if let Some(fix) = merge_component_params(params, &hooks, source_text)? {
+        fixes.push(fix);
+    }
+
+    if is_typescript {
+        match params.items.first() {
+            None => {
+                let position = params.span.end - 1;
+                fixes.push(annotation_fix(
+                    ": Route.ComponentProps".to_owned(),
+                    Span::new(position, position),
+                ));
+            }
+            Some(param) if param.pattern.type_annotation.is_none() => {
+                fixes.push(annotate_first_param(
+                    params,
+                    "Route.ComponentProps",
+                    source_text,
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(fixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::SourceType;
+
+    use super::typegen;
+    use crate::{
+        codemod_models::CodemodOptions,
+        test_utils::{assert_transform_snapshot, outdent},
+    };
+
+    #[test]
+    fn test_typegen_kitchen_sink() {
+        let input = r#"
+            import type {
+              ActionFunctionArgs, LoaderFunctionArgs, LinksFunction, MetaFunction,
+            } from "@remix-run/node";
+            import { useActionData, useLoaderData } from "@remix-run/react";
+
+            export const links: LinksFunction = () => [{ rel: "icon", href: "/favicon.png" }];
+
+            export const meta: MetaFunction<typeof loader> = ({ data }) => [{ title: data.title }];
+
+            export async function loader({ params }: LoaderFunctionArgs) {
+              return { title: params.title };
+            }
+
+            export const action = async ({ request }: ActionFunctionArgs) => {
+              return { ok: true };
+            };
+
+            export const clientLoader = async ({ serverLoader }) => {
+              return await serverLoader();
+            };
+
+            export default function Component() {
+              const data = useLoaderData<typeof loader>();
+              const actionData = useActionData<typeof action>();
+              return <h1>{data.title} {actionData?.ok}</h1>;
+            }
+        "#;
+        assert_snapshot("typegen_kitchen_sink", input);
+    }
+
+    #[test]
+    fn test_typegen_component_arrow_function_with_args() {
+        let input = r#"
+            export const loader = args => ({ hello: "world" });
+
+            export default ({ params }) => {
+              const { hello } = useLoaderData();
+              return <h1>{hello}</h1>;
+            };
+        "#;
+        assert_snapshot("typegen_component_arrow_function_with_args", input);
+    }
+
+    #[test]
+    fn test_typegen_without_route_exports() {
+        let input = r#"
+            export default function Component() {
+              return <h1>Hello</h1>;
+            }
+        "#;
+        assert_snapshot("typegen_without_route_exports", input);
+    }
+
+    #[test]
+    fn test_typegen_component_identifier() {
+        let input = r#"
+            import { useLoaderData } from "@remix-run/react";
+
+            export function loader() {
+              return { hello: "world" };
+            }
+
+            function UsersPage() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.hello}</h1>;
+            }
+
+            export default UsersPage;
+        "#;
+        assert_snapshot("typegen_component_identifier", input);
+    }
+
+    #[test]
+    fn test_typegen_javascript_route() {
+        let input = r#"
+            import { useLoaderData } from "@remix-run/react";
+
+            export const loader = async ({ params }) => ({ id: params.id });
+
+            export default function User() {
+              const data = useLoaderData();
+              return <h1>{data.id}</h1>;
+            }
+        "#;
+        assert_snapshot_with_path("typegen_javascript_route", input, "path/to/users.$id.jsx");
+    }
+
+    #[test]
+    fn test_typegen_rewrite_imports() {
+        let input = outdent(
+            r#"
+            import { Link, useLoaderData } from "@remix-run/react";
+            import type { LoaderFunctionArgs } from "@remix-run/node";
+
+            export async function loader({ params }: LoaderFunctionArgs) {
+              return { id: params.id };
+            }
+
+            export default function User() {
+              const data = useLoaderData<typeof loader>();
+              return <Link to="/">{data.id}</Link>;
+            }
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/users.$id.tsx").unwrap();
+        let options = CodemodOptions {
+            route_name: Some("users.$id".to_owned()),
+            rewrite_imports: true,
+            ..Default::default()
+        };
+        let output = typegen(&input, source_type, &options).unwrap();
+        assert_eq!(
+            output.code,
+            outdent(
+                r#"
+                import { Link } from "react-router";
+                import type { Route } from "./+types/users.$id";
+
+                export async function loader({ params }: Route.LoaderArgs) {
+                  return { id: params.id };
+                }
+
+                export default function User({ loaderData: data }: Route.ComponentProps) {
+                  return <Link to="/">{data.id}</Link>;
+                }
+            "#
+            )
+        );
+    }
+
+    fn assert_snapshot(name: &str, input: &str) {
+        assert_snapshot_with_path(name, input, "path/to/users.$id.tsx");
+    }
+
+    fn assert_snapshot_with_path(name: &str, input: &str, path: &str) {
+        let options = CodemodOptions {
+            route_name: Some("users.$id".to_owned()),
+            ..Default::default()
+        };
+        assert_transform_snapshot(name, input, path, &options, typegen);
+    }
+}
@@ -1,5 +1,9 @@
 use serde_json::Value;
+
+use crate::cli::SourceMapOutput;
+use crate::codemod_models::ManifestRoute;
 use std::env;
+use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::exit;
@@ -47,6 +51,42 @@ pub fn traverse_route_entry(entry: Value) -> Vec<String> {
     files
 }
 
+/// Routes of a route entry and its children, with the URL pattern they
+/// match under `parent_path`
+pub fn traverse_route_ids(entry: &Value, parent_path: Option<&str>) -> Vec<ManifestRoute> {
+    let mut routes = vec![];
+
+    let own_path = entry["path"].as_str().filter(|path| !path.is_empty());
+    let full_path = match own_path {
+        Some(path) => Some(format!(
+            "{}/{}",
+            parent_path.unwrap_or("").trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )),
+        None => parent_path.map(|path| path.to_owned()),
+    };
+    if let (Some(id), Some(file)) = (entry["id"].as_str(), entry["file"].as_str()) {
+        // Layout routes without a path don't match a URL of their own
+        let is_index = entry["index"].as_bool().unwrap_or(false);
+        routes.push(ManifestRoute {
+            id: id.to_owned(),
+            file: file.to_owned(),
+            path: if own_path.is_some() || is_index {
+                Some(full_path.clone().unwrap_or_else(|| "/".to_owned()))
+            } else {
+                None
+            },
+        });
+    }
+    if let Some(array) = entry["children"].as_array() {
+        for item in array {
+            routes.extend(traverse_route_ids(item, full_path.as_deref()));
+        }
+    }
+
+    routes
+}
+
 pub fn get_remix_routes_json(current_dir: &String) -> String {
     let output = Command::new("npx")
         .arg("-y")
@@ -58,8 +98,7 @@ pub fn get_remix_routes_json(current_dir: &String) -> String {
         .expect("Failed to execute command");
 
     if output.status.success() {
-        let files = String::from_utf8(output.stdout).unwrap();
-        files
+        String::from_utf8(output.stdout).unwrap()
     } else {
         let error = String::from_utf8(output.stderr).unwrap();
         eprintln!("Error: {}", error);
@@ -67,11 +106,10 @@ pub fn get_remix_routes_json(current_dir: &String) -> String {
     }
 }
 
-pub fn get_resolved_dir() -> String {
-    let args: Vec<String> = env::args().collect();
+pub fn get_resolved_dir(dir: Option<&String>) -> String {
     let current_dir = env::current_dir().expect("Failed to get current directory");
-    if args.len() > 1 {
-        let path = Path::new(&args[1]);
+    if let Some(dir) = dir {
+        let path = Path::new(dir);
         let absolute_path = if path.is_relative() {
             current_dir.join(path)
         } else {
@@ -88,3 +126,111 @@ pub fn get_resolved_dir() -> String {
         current_dir.display().to_string()
     }
 }
+
+pub fn get_source_map_path(
+    file_path: &str,
+    current_dir: &String,
+    output: &SourceMapOutput,
+) -> PathBuf {
+    match output {
+        SourceMapOutput::NextToOutput => PathBuf::from(format!("{}.map", file_path)),
+        SourceMapOutput::Directory(dir) => {
+            let relative_path = Path::new(file_path)
+                .strip_prefix(current_dir)
+                .unwrap_or(Path::new(file_path));
+            let dir = env::current_dir()
+                .expect("Failed to get current directory")
+                .join(dir);
+            dir.join(format!("{}.map", relative_path.display()))
+        }
+    }
+}
+
+/// Path of the transformed file as the `sources` entry of its source map,
+/// relative to the map so the two can move together
+pub fn get_source_map_source(map_path: &Path, file_path: &Path) -> String {
+    let map_dir = map_path
+        .parent()
+        .map_or(vec![], |dir| dir.components().collect());
+    let file = file_path.components().collect::<Vec<_>>();
+    let common = map_dir
+        .iter()
+        .zip(file.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut segments = vec![String::from(".."); map_dir.len() - common];
+    segments.extend(
+        file[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+    segments.join("/")
+}
+
+/// One-based line and column of a byte offset
+pub fn get_line_and_column(source_text: &str, offset: u32) -> (usize, usize) {
+    let before = &source_text[..offset as usize];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// TS and JS modules in the project, without dependencies, build output and
+/// hidden directories
+pub fn get_project_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    let mut entries = entries.flatten().map(|e| e.path()).collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.')
+                && !["node_modules", "build", "public"].contains(&name.as_str())
+            {
+                files.extend(get_project_files(&path));
+            }
+        } else if !name.ends_with(".d.ts")
+            && path.extension().is_some_and(|ext| {
+                ["ts", "tsx", "js", "jsx"].contains(&ext.to_string_lossy().as_ref())
+            })
+        {
+            files.push(path);
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::get_source_map_source;
+
+    #[test]
+    fn test_source_map_source() {
+        assert_eq!(
+            get_source_map_source(
+                Path::new("/app/routes/users.tsx.map"),
+                Path::new("/app/routes/users.tsx")
+            ),
+            "users.tsx"
+        );
+        assert_eq!(
+            get_source_map_source(
+                Path::new("/maps/app/routes/users.tsx.map"),
+                Path::new("/app/routes/users.tsx")
+            ),
+            "../../../app/routes/users.tsx"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Post-transform sanity check. The output must parse without semantic errors
+//! like redeclared bindings, and it must not reference anything that wasn't
+//! already unresolved in the original file, e.g. a removed hook binding or a
+//! renamed `loader` that is still used.
+
+use std::collections::HashSet;
+
+use oxc_allocator::Allocator;
+use oxc_parser::Parser;
+use oxc_semantic::SemanticBuilder;
+use oxc_span::SourceType;
+
+use crate::{
+    codemod_models::{BrokenReference, CodemodError},
+    utils::get_line_and_column,
+};
+
+/// Checks the output, where `introduced_globals` are the helpers the codemod
+/// itself adds, like `defineRoute`
+pub fn verify(
+    original_source_text: &str,
+    source_text: &str,
+    source_type: SourceType,
+    introduced_globals: &[&str],
+) -> Result<(), CodemodError> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+
+    if !ret.errors.is_empty() {
+        return Err(CodemodError::InvalidOutput(
+            ret.errors.iter().map(|e| e.to_string()).collect(),
+        ));
+    }
+
+    let semantic_ret = SemanticBuilder::new(source_text, source_type)
+        .with_trivias(ret.trivias)
+        .build(&ret.program);
+
+    if !semantic_ret.errors.is_empty() {
+        return Err(CodemodError::InvalidOutput(
+            semantic_ret.errors.iter().map(|e| e.to_string()).collect(),
+        ));
+    }
+    let semantic = semantic_ret.semantic;
+
+    let previously_unresolved = get_unresolved_names(original_source_text, source_type);
+
+    let mut broken = vec![];
+    for (name, reference_ids) in semantic.scopes().root_unresolved_references() {
+        if previously_unresolved.contains(name.as_str())
+            || introduced_globals.contains(&name.as_str())
+        {
+            continue;
+        }
+        for reference_id in reference_ids {
+            let span = semantic.symbols().get_reference(*reference_id).span();
+            let (line, column) = get_line_and_column(source_text, span.start);
+            broken.push(BrokenReference {
+                name: name.to_string(),
+                line,
+                column,
+            });
+        }
+    }
+
+    if broken.is_empty() {
+        Ok(())
+    } else {
+        broken.sort_by_key(|b| (b.line, b.column));
+        Err(CodemodError::BrokenReferences(broken))
+    }
+}
+
+fn get_unresolved_names(source_text: &str, source_type: SourceType) -> HashSet<String> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+    let semantic_ret = SemanticBuilder::new(source_text, source_type)
+        .with_trivias(ret.trivias)
+        .build(&ret.program);
+
+    semantic_ret
+        .semantic
+        .scopes()
+        .root_unresolved_references()
+        .keys()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_span::SourceType;
+
+    use super::verify;
+    use crate::{codemod_models::CodemodError, test_utils::outdent};
+
+    #[test]
+    fn test_broken_references() {
+        let original = outdent(
+            r#"
+            export async function loader() {
+              return { name: "Jane" };
+            }
+
+            export default function Component() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.name}</h1>;
+            }
+        "#,
+        );
+        // `loader` moved while the type reference in the other component stays
+        let output = outdent(
+            r#"
+            function Other() {
+              const data = useLoaderData<typeof loader>();
+              return <h1>{data.name}</h1>;
+            }
+
+            export default defineRoute({
+              async serverLoader() {
+                return { name: "Jane" };
+              },
+              Component: Other,
+            });
+        "#,
+        );
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        match verify(&original, &output, source_type, &["defineRoute"]) {
+            Err(CodemodError::BrokenReferences(broken)) => {
+                let broken = broken
+                    .iter()
+                    .map(|b| (b.name.as_str(), b.line, b.column))
+                    .collect::<Vec<_>>();
+                assert_eq!(broken, vec![("loader", 2, 37)]);
+            }
+            result => panic!("expected broken references, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_previously_unresolved_references() {
+        let original = "console.log(getUser());\n";
+        let output = "export default defineRoute({ user: getUser() });\nconsole.log(1);\n";
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        assert!(verify(original, output, source_type, &["defineRoute"]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_output() {
+        let original = "export const meta = () => [];\n";
+        let output = "export default defineRoute({\n  meta: () => [],\n";
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        assert!(matches!(
+            verify(original, output, source_type, &["defineRoute"]),
+            Err(CodemodError::InvalidOutput(errors)) if !errors.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_binding() {
+        let original =
+            "async function loader() {}\nexport default defineRoute({ serverLoader: loader });\n";
+        // A fix that wraps the kept binding instead of re-exporting it
+        let output = "async function loader() {}\nexport const loader = loader;\n";
+        let source_type = SourceType::from_path("path/to/file.tsx").unwrap();
+        assert!(matches!(
+            verify(original, output, source_type, &[]),
+            Err(CodemodError::InvalidOutput(errors)) if !errors.is_empty()
+        ));
+    }
+}